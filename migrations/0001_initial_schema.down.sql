@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS comments;
+DROP TABLE IF EXISTS categories_relationships;
+DROP TABLE IF EXISTS categories;
+DROP TABLE IF EXISTS posts;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS posts_search_vector_trigger ON posts;
+DROP FUNCTION IF EXISTS posts_search_vector_update();
+DROP INDEX IF EXISTS posts_search_vector_idx;
+ALTER TABLE posts DROP COLUMN IF EXISTS search_vector;
+DROP FUNCTION IF EXISTS posts_search_vector(TEXT, TEXT, TEXT, REGCONFIG);
+DROP FUNCTION IF EXISTS blog_strip_html(TEXT);
+DROP FUNCTION IF EXISTS blog_search_normalize(TEXT);
+DROP TABLE IF EXISTS search_settings;
@@ -0,0 +1,50 @@
+-- Full-text search over posts. The text search configuration lives in
+-- search_settings so it can be switched (SEARCH_CONFIG) without a new migration;
+-- the server rewrites it and reindexes when the configured value changes.
+
+CREATE TABLE search_settings (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    config REGCONFIG NOT NULL DEFAULT 'turkish'
+);
+INSERT INTO search_settings DEFAULT VALUES;
+
+-- PostgreSQL lowercases 'I' to 'i' and 'İ' to 'i̇', which is wrong for Turkish.
+-- Map them to 'ı' and 'i' before the parser sees the text.
+CREATE FUNCTION blog_search_normalize(input TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE AS $$
+    SELECT translate(COALESCE(input, ''), 'İI', 'iı')
+$$;
+
+CREATE FUNCTION blog_strip_html(input TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE AS $$
+    SELECT regexp_replace(COALESCE(input, ''), '<[^>]*>', ' ', 'g')
+$$;
+
+CREATE FUNCTION posts_search_vector(title TEXT, excerpt TEXT, content TEXT, config REGCONFIG) RETURNS TSVECTOR
+    LANGUAGE sql IMMUTABLE AS $$
+    SELECT
+        setweight(to_tsvector(config, blog_search_normalize(title)), 'A') ||
+        setweight(to_tsvector(config, blog_search_normalize(excerpt)), 'B') ||
+        setweight(to_tsvector(config, blog_search_normalize(blog_strip_html(content))), 'C')
+$$;
+
+ALTER TABLE posts ADD COLUMN search_vector TSVECTOR;
+
+CREATE FUNCTION posts_search_vector_update() RETURNS TRIGGER
+    LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.search_vector := posts_search_vector(
+        NEW.title, NEW.excerpt, NEW.content,
+        (SELECT config FROM search_settings)
+    );
+    RETURN NEW;
+END
+$$;
+
+CREATE TRIGGER posts_search_vector_trigger
+    BEFORE INSERT OR UPDATE OF title, excerpt, content ON posts
+    FOR EACH ROW EXECUTE FUNCTION posts_search_vector_update();
+
+UPDATE posts SET search_vector = posts_search_vector(title, excerpt, content, 'turkish');
+
+CREATE INDEX posts_search_vector_idx ON posts USING GIN (search_vector);
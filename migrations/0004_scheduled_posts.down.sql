@@ -0,0 +1,4 @@
+UPDATE posts SET status = 'draft' WHERE status = 'scheduled';
+
+DROP INDEX IF EXISTS posts_scheduled_idx;
+DROP INDEX IF EXISTS posts_published_idx;
@@ -0,0 +1,9 @@
+-- Public listings: published posts, newest first.
+CREATE INDEX IF NOT EXISTS posts_published_idx
+    ON posts (published_at DESC, id DESC)
+    WHERE status = 'published';
+
+-- The scheduler looks for scheduled posts whose time has come.
+CREATE INDEX IF NOT EXISTS posts_scheduled_idx
+    ON posts (published_at)
+    WHERE status = 'scheduled';
@@ -0,0 +1,19 @@
+CREATE OR REPLACE FUNCTION posts_search_vector_update() RETURNS TRIGGER
+    LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.search_vector := posts_search_vector(
+        NEW.title, NEW.excerpt, NEW.content,
+        (SELECT config FROM search_settings)
+    );
+    RETURN NEW;
+END
+$$;
+
+DROP TRIGGER IF EXISTS posts_search_vector_trigger ON posts;
+CREATE TRIGGER posts_search_vector_trigger
+    BEFORE INSERT OR UPDATE OF title, excerpt, content ON posts
+    FOR EACH ROW EXECUTE FUNCTION posts_search_vector_update();
+
+ALTER TABLE posts
+    DROP COLUMN IF EXISTS content_html,
+    DROP COLUMN IF EXISTS content_format;
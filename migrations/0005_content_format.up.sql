@@ -0,0 +1,24 @@
+-- `content` keeps what the author wrote; `content_html` is what gets served.
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS content_format VARCHAR(10) NOT NULL DEFAULT 'html'
+        CHECK (content_format IN ('html', 'markdown')),
+    ADD COLUMN IF NOT EXISTS content_html TEXT;
+
+UPDATE posts SET content_html = content WHERE content_html IS NULL;
+
+-- Index the rendered HTML so markdown syntax doesn't end up in the search vector.
+CREATE OR REPLACE FUNCTION posts_search_vector_update() RETURNS TRIGGER
+    LANGUAGE plpgsql AS $$
+BEGIN
+    NEW.search_vector := posts_search_vector(
+        NEW.title, NEW.excerpt, COALESCE(NEW.content_html, NEW.content),
+        (SELECT config FROM search_settings)
+    );
+    RETURN NEW;
+END
+$$;
+
+DROP TRIGGER IF EXISTS posts_search_vector_trigger ON posts;
+CREATE TRIGGER posts_search_vector_trigger
+    BEFORE INSERT OR UPDATE OF title, excerpt, content, content_html ON posts
+    FOR EACH ROW EXECUTE FUNCTION posts_search_vector_update();
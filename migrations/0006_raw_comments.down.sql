@@ -0,0 +1,3 @@
+UPDATE comments SET
+    author_name = replace(replace(replace(author_name, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+    content = replace(replace(replace(content, '&', '&amp;'), '<', '&lt;'), '>', '&gt;');
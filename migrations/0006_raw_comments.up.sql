@@ -0,0 +1,5 @@
+-- Comments used to be HTML-escaped on insert; they are now stored as typed and
+-- escaped when rendered. Undo the old escaping (only &, < and > were encoded).
+UPDATE comments SET
+    author_name = replace(replace(replace(author_name, '&lt;', '<'), '&gt;', '>'), '&amp;', '&'),
+    content = replace(replace(replace(content, '&lt;', '<'), '&gt;', '>'), '&amp;', '&');
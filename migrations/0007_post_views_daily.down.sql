@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS posts_view_count_idx;
+DROP TABLE IF EXISTS post_views_daily;
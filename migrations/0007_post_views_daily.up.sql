@@ -0,0 +1,14 @@
+-- Views per post per day, for "trending" lists over a recent window.
+CREATE TABLE IF NOT EXISTS post_views_daily (
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    views INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (post_id, day)
+);
+
+CREATE INDEX IF NOT EXISTS post_views_daily_day_idx ON post_views_daily (day);
+
+-- "Most read" lists sort published posts by their total.
+CREATE INDEX IF NOT EXISTS posts_view_count_idx
+    ON posts (view_count DESC)
+    WHERE status = 'published';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS categories_relationships_term_idx;
+DROP INDEX IF EXISTS categories_relationships_post_idx;
@@ -0,0 +1,7 @@
+-- Category lookups in both directions: a post's categories, and the posts
+-- of a category (category-scoped previous/next links, related posts).
+CREATE INDEX IF NOT EXISTS categories_relationships_post_idx
+    ON categories_relationships (post_id);
+
+CREATE INDEX IF NOT EXISTS categories_relationships_term_idx
+    ON categories_relationships (term_id, post_id);
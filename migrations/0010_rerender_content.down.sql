@@ -0,0 +1,3 @@
+-- Nothing to undo: the re-rendered HTML is what the previous version would
+-- have produced on save anyway.
+SELECT 1;
@@ -0,0 +1,5 @@
+-- Posts saved before the sanitizer existed hold their raw HTML in
+-- content_html (0005 copied it over, the seed posts bound it directly).
+-- Clearing it makes the server render and clean every post again at startup
+-- (content::render_missing); until then nothing but content_html is served.
+UPDATE posts SET content_html = NULL;
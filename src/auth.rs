@@ -0,0 +1,308 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use actix_web::cookie::{Cookie, SameSite, time::Duration as CookieDuration};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::error::{self, AppError};
+use crate::models::api_tokens::{self, ApiToken};
+use crate::models::blogs::Blogs;
+use crate::models::sessions::Session;
+use crate::models::users::User;
+use crate::permissions::{Permission, Role};
+use crate::state::AppState;
+
+pub const SESSION_COOKIE: &str = "skyfetch_session";
+
+// Hash of a random password, verified against when the login name is unknown
+// so that response timing doesn't reveal which usernames exist.
+fn dummy_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(salt.as_str().as_bytes(), &salt)
+            .map(|h| h.to_string())
+            .unwrap_or_default()
+    })
+}
+
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|h| h.to_string())
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Checks a login/password pair against `users.password_hash`.
+/// Argon2 is slow on purpose, so it runs on the blocking thread pool.
+pub async fn authenticate(user: Option<User>, password: String) -> Option<User> {
+    let hash = user.as_ref().map(|u| u.password_hash.clone());
+
+    let valid = web::block(move || match hash {
+        Some(hash) => verify_password(&password, &hash),
+        None => verify_password(&password, dummy_hash()),
+    })
+        .await
+        .unwrap_or(false);
+
+    if valid { user } else { None }
+}
+
+pub fn session_cookie(config: &Config, token: String) -> Cookie<'static> {
+    Cookie::build(SESSION_COOKIE, token)
+        .path("/")
+        .http_only(true)
+        .secure(config.cookie_secure)
+        .same_site(SameSite::Lax)
+        .max_age(CookieDuration::seconds(config.session_ttl.as_secs() as i64))
+        .finish()
+}
+
+pub fn removal_cookie(config: &Config) -> Cookie<'static> {
+    let mut cookie = session_cookie(config, String::new());
+    cookie.make_removal();
+    cookie
+}
+
+/// Extractor for handlers that require a logged-in staff member (any role
+/// with `Permission::AccessAdmin`). Handlers narrow it further with
+/// `require` or the post-level checks on `Role`. Rejections are `AppError`s,
+/// so admin pages get the login redirect or the error page and `/api` gets
+/// the JSON envelope.
+pub struct AuthenticatedUser {
+    pub user: User,
+    pub role: Role,
+}
+
+impl AuthenticatedUser {
+    pub fn new(user: User) -> Self {
+        let role = Role::parse(&user.role);
+        AuthenticatedUser { user, role }
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if self.role.has(permission) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+
+    pub fn can_edit_post(&self, post: &Blogs) -> bool {
+        self.role.can_edit_post(self.user.id, post.author_id, post.status.as_deref())
+    }
+
+    pub fn can_set_status(&self, post_author_id: i32, status: &str) -> bool {
+        self.role.can_set_status(self.user.id, post_author_id, status)
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = AppError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let state = req.app_data::<web::Data<AppState>>().cloned();
+        let token = req.cookie(SESSION_COOKIE).map(|c| c.value().to_string());
+
+        Box::pin(async move {
+            let (state, token) = match (state, token) {
+                (Some(state), Some(token)) if !token.is_empty() => (state, token),
+                (None, _) => return Err(AppError::Internal("App state is not configured".to_string())),
+                _ => return Err(AppError::Unauthenticated),
+            };
+
+            let user = Session::find_user(&state.pool, &token)
+                .await?
+                .map(AuthenticatedUser::new)
+                .ok_or(AppError::Unauthenticated)?;
+
+            user.require(Permission::AccessAdmin)?;
+            Ok(user)
+        })
+    }
+}
+
+/// Extractor for the write API: a staff member authenticated with an
+/// `Authorization: Bearer skf_...` API token. Handlers check the token's
+/// scopes with `require_scope` and the role as for `AuthenticatedUser`.
+pub struct TokenUser {
+    pub auth: AuthenticatedUser,
+    pub token: ApiToken,
+}
+
+impl TokenUser {
+    pub fn require_scope(&self, scope: &str) -> Result<(), AppError> {
+        if self.token.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+}
+
+impl FromRequest for TokenUser {
+    type Error = AppError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let state = req.app_data::<web::Data<AppState>>().cloned();
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|v| v.trim().to_string());
+
+        Box::pin(async move {
+            let Some(state) = state else {
+                return Err(AppError::Internal("App state is not configured".to_string()));
+            };
+            let token = token.ok_or(AppError::Unauthenticated)?;
+            let (prefix, secret) = api_tokens::parse_token(&token).ok_or(AppError::Unauthenticated)?;
+
+            let (api_token, user) = ApiToken::find_by_prefix(&state.pool, prefix)
+                .await?
+                .ok_or(AppError::Unauthenticated)?;
+
+            let hash = api_token.token_hash.clone();
+            let secret = secret.to_string();
+            let valid = web::block(move || verify_password(&secret, &hash))
+                .await
+                .unwrap_or(false);
+            if !valid || api_token.is_expired() {
+                return Err(AppError::Unauthenticated);
+            }
+
+            // Only bookkeeping, so a failure doesn't reject the request.
+            if let Err(e) = ApiToken::touch(&state.pool, api_token.id).await {
+                error::log_ignored(e);
+            }
+
+            let auth = AuthenticatedUser::new(user);
+            auth.require(Permission::AccessAdmin)?;
+            Ok(TokenUser { auth, token: api_token })
+        })
+    }
+}
+
+/// Optional credentials for the read API, which only matter for unpublished
+/// posts: an admin session, or a bearer token with `posts:read` or
+/// `posts:write`. Anonymous requests, stale sessions and tokens without those
+/// scopes read as `None`; a bad token is still rejected as on the write API,
+/// and a failed lookup is an error rather than an anonymous request.
+pub struct ApiReader(pub Option<AuthenticatedUser>);
+
+impl ApiReader {
+    fn from_token(result: Result<TokenUser, AppError>) -> Result<Self, AppError> {
+        match result {
+            Ok(user) if ["posts:read", "posts:write"].iter().any(|scope| user.token.has_scope(scope)) => {
+                Ok(ApiReader(Some(user.auth)))
+            }
+            Ok(_) | Err(AppError::Forbidden) => Ok(ApiReader(None)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn from_session(result: Result<AuthenticatedUser, AppError>) -> Result<Self, AppError> {
+        match result {
+            Ok(user) => Ok(ApiReader(Some(user))),
+            Err(e @ (AppError::Database(_) | AppError::Internal(_))) => Err(e),
+            Err(_) => Ok(ApiReader(None)),
+        }
+    }
+}
+
+impl FromRequest for ApiReader {
+    type Error = AppError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if req.headers().contains_key(header::AUTHORIZATION) {
+            let token = TokenUser::from_request(req, payload);
+            Box::pin(async move { ApiReader::from_token(token.await) })
+        } else {
+            let session = AuthenticatedUser::from_request(req, payload);
+            Box::pin(async move { ApiReader::from_session(session.await) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn token_user(role: &str, scopes: &[&str]) -> TokenUser {
+        let user = User {
+            id: 7,
+            username: "writer".to_string(),
+            email: "writer@example.com".to_string(),
+            password_hash: String::new(),
+            full_name: None,
+            bio: None,
+            profile_image: None,
+            role: role.to_string(),
+            created_at: None,
+            updated_at: None,
+        };
+        let token = ApiToken {
+            id: 1,
+            user_id: user.id,
+            name: "test".to_string(),
+            prefix: "0123456789abcdef".to_string(),
+            token_hash: String::new(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_at: None,
+            last_used_at: None,
+            created_at: Utc::now(),
+        };
+        TokenUser { auth: AuthenticatedUser::new(user), token }
+    }
+
+    fn reader_id(result: Result<ApiReader, AppError>) -> Option<i32> {
+        result.expect("token should be accepted").0.map(|auth| auth.user.id)
+    }
+
+    #[test]
+    fn token_with_a_posts_scope_reads_as_its_user() {
+        assert_eq!(reader_id(ApiReader::from_token(Ok(token_user("author", &["posts:read"])))), Some(7));
+        assert_eq!(reader_id(ApiReader::from_token(Ok(token_user("author", &["posts:write"])))), Some(7));
+    }
+
+    #[test]
+    fn token_without_a_posts_scope_reads_as_anonymous() {
+        assert_eq!(reader_id(ApiReader::from_token(Ok(token_user("editor", &["comments:moderate"])))), None);
+        assert_eq!(reader_id(ApiReader::from_token(Err(AppError::Forbidden))), None);
+    }
+
+    #[test]
+    fn bad_token_and_failed_lookup_are_errors() {
+        assert!(matches!(ApiReader::from_token(Err(AppError::Unauthenticated)), Err(AppError::Unauthenticated)));
+        assert!(matches!(
+            ApiReader::from_token(Err(AppError::Database(sqlx::Error::PoolTimedOut))),
+            Err(AppError::Database(_))
+        ));
+    }
+
+    #[test]
+    fn stale_session_reads_as_anonymous() {
+        assert_eq!(reader_id(ApiReader::from_session(Err(AppError::Unauthenticated))), None);
+        assert!(ApiReader::from_session(Err(AppError::Database(sqlx::Error::PoolTimedOut))).is_err());
+    }
+}
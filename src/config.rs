@@ -0,0 +1,114 @@
+use std::env;
+use std::time::Duration;
+
+use crate::content::sanitize;
+use crate::models::popular::MAX_WINDOW_DAYS;
+
+// Runtime settings read from the environment (or .env) once at startup.
+#[derive(Clone, Debug)]
+pub struct Config {
+    // Send the session cookie with the Secure flag. Only disable for local
+    // development over plain http.
+    pub cookie_secure: bool,
+    pub session_ttl: Duration,
+    // Levels of nesting allowed in comment threads; 1 disables replies.
+    pub comment_max_depth: usize,
+    // PostgreSQL text search configuration used to index and query posts.
+    pub search_config: String,
+    pub posts_per_page: i64,
+    // How often the scheduler looks for scheduled posts that are due.
+    pub scheduler_interval: Duration,
+    // Public base URL without a trailing slash, used for absolute links in feeds.
+    pub site_url: String,
+    pub site_title: String,
+    pub site_description: String,
+    // Put the whole post in feed entries instead of only the excerpt.
+    pub feed_full_content: bool,
+    pub feed_limit: i64,
+    // Paths listed as `Disallow` in /robots.txt.
+    pub robots_disallow: Vec<String>,
+    // Post HTML allow-list on top of the built-in one: extra tags, extra
+    // attributes allowed on any tag, and the URL schemes links may use.
+    pub sanitize_tags: Vec<String>,
+    pub sanitize_attributes: Vec<String>,
+    pub sanitize_url_schemes: Vec<String>,
+    // A visitor counts as one view per post within this window.
+    pub view_window: Duration,
+    pub view_flush_interval: Duration,
+    // Take the client IP from Forwarded / X-Forwarded-For. Only enable behind
+    // a reverse proxy that sets them, otherwise clients can fake their IP.
+    pub trust_proxy: bool,
+    // Length of the popular posts lists and how many days "trending" covers.
+    pub popular_limit: i64,
+    pub trending_days: i32,
+    pub popular_cache_ttl: Duration,
+    pub related_posts_limit: i64,
+    // Comments one client IP may submit per window.
+    pub comment_rate_limit: u32,
+    pub comment_rate_window: Duration,
+    // Write API requests (post and comment changes) one user may make per minute.
+    pub api_write_rate_limit: u32,
+    // Posts stop taking comments this many days after publication; 0 never closes them.
+    pub comments_close_after_days: i32,
+}
+
+fn env_bool(key: &str, default: bool) -> bool {
+    match env::var(key) {
+        Ok(v) => !matches!(v.to_lowercase().as_str(), "false" | "0" | "no" | "off"),
+        Err(_) => default,
+    }
+}
+
+// Comma-separated list; empty items are dropped.
+fn env_list(key: &str, default: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> T {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Config {
+            cookie_secure: env_bool("COOKIE_SECURE", true),
+            session_ttl: Duration::from_secs(env_parse("SESSION_TTL_HOURS", 24u64) * 60 * 60),
+            comment_max_depth: env_parse("COMMENT_MAX_DEPTH", 3usize).max(1),
+            search_config: env::var("SEARCH_CONFIG").unwrap_or_else(|_| "turkish".to_string()),
+            posts_per_page: env_parse("POSTS_PER_PAGE", 9i64).max(1),
+            scheduler_interval: Duration::from_secs(env_parse("SCHEDULER_INTERVAL_SECS", 60u64).max(1)),
+            site_url: env::var("SITE_URL")
+                .unwrap_or_else(|_| "http://localhost:8080".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            site_title: env::var("SITE_TITLE").unwrap_or_else(|_| "Skyfetch".to_string()),
+            site_description: env::var("SITE_DESCRIPTION")
+                .unwrap_or_else(|_| "Düşüncelerimi ve deneyimlerimi paylaştığım blog".to_string()),
+            feed_full_content: env_bool("FEED_FULL_CONTENT", true),
+            feed_limit: env_parse("FEED_LIMIT", 20i64).clamp(1, 100),
+            robots_disallow: env_list("ROBOTS_DISALLOW", "/admin,/api,/search"),
+            sanitize_tags: sanitize::allowed_tags(env_list("SANITIZE_TAGS", "")),
+            sanitize_attributes: sanitize::allowed_attributes(env_list("SANITIZE_ATTRIBUTES", "")),
+            sanitize_url_schemes: env_list("SANITIZE_URL_SCHEMES", "http,https,mailto"),
+            view_window: Duration::from_secs(env_parse("VIEW_WINDOW_MINUTES", 30u64).max(1) * 60),
+            view_flush_interval: Duration::from_secs(env_parse("VIEW_FLUSH_SECS", 30u64).max(1)),
+            trust_proxy: env_bool("TRUST_PROXY", false),
+            popular_limit: env_parse("POPULAR_LIMIT", 5i64).clamp(1, 50),
+            trending_days: env_parse("TRENDING_DAYS", 7i32).clamp(1, MAX_WINDOW_DAYS),
+            popular_cache_ttl: Duration::from_secs(env_parse("POPULAR_CACHE_SECS", 300u64).max(1)),
+            related_posts_limit: env_parse("RELATED_POSTS_LIMIT", 3i64).clamp(0, 20),
+            comment_rate_limit: env_parse("COMMENT_RATE_LIMIT", 5u32).max(1),
+            comment_rate_window: Duration::from_secs(env_parse("COMMENT_RATE_WINDOW_MINUTES", 10u64).max(1) * 60),
+            api_write_rate_limit: env_parse("API_WRITE_RATE_LIMIT", 60u32).max(1),
+            comments_close_after_days: env_parse("COMMENTS_CLOSE_AFTER_DAYS", 0i32).max(0),
+        }
+    }
+}
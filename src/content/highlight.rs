@@ -0,0 +1,86 @@
+use html_escape::decode_html_entities;
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+// Prefixed so theme classes like `hl-string` can't clash with the site's CSS.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const BLOCK_START: &str = "<pre><code class=\"language-";
+const BLOCK_END: &str = "</code></pre>";
+
+pub const DEFAULT_THEME: &str = "InspiredGitHub";
+
+// syntect's own set lacks TOML; two-face adds it along with many others.
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(two_face::syntax::extra_newlines)
+}
+
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let token = match language.to_ascii_lowercase().as_str() {
+        "shell" | "sh" | "zsh" | "console" => "bash".to_string(),
+        "javascript" => "js".to_string(),
+        other => other.to_string(),
+    };
+    syntaxes().find_syntax_by_token(&token)
+}
+
+fn highlight_block(language: &str, code: &str) -> Option<String> {
+    // Blocks with markup inside (hand-written HTML posts) are left alone.
+    if code.contains('<') {
+        return None;
+    }
+    let syntax = find_syntax(language)?;
+    let code = decode_html_entities(code);
+
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes(), CLASS_STYLE);
+    for line in LinesWithEndings::from(&code) {
+        generator.parse_html_for_line_which_includes_newline(line).ok()?;
+    }
+
+    Some(format!(
+        "<pre class=\"hl-code\"><code class=\"language-{}\">{}</code></pre>",
+        language,
+        generator.finalize()
+    ))
+}
+
+/// Highlights every `<pre><code class="language-x">` block in sanitized HTML,
+/// wrapping tokens in `hl-*` classed spans. Unknown languages are left as they are.
+pub fn highlight_code_blocks(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find(BLOCK_START) {
+        output.push_str(&rest[..start]);
+        let block = &rest[start..];
+        let Some(end) = block.find(BLOCK_END) else {
+            break;
+        };
+        let end = end + BLOCK_END.len();
+
+        let highlighted = block[BLOCK_START.len()..end - BLOCK_END.len()]
+            .split_once("\">")
+            .and_then(|(class, code)| {
+                let language = class.split_whitespace().next()?;
+                highlight_block(language, code)
+            });
+        output.push_str(highlighted.as_deref().unwrap_or(&block[..end]));
+        rest = &block[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Stylesheet for the `hl-*` classes in one of syntect's bundled themes.
+pub fn theme_css(theme: &str) -> Result<String, String> {
+    let themes = ThemeSet::load_defaults();
+    let selected = themes.themes.get(theme).ok_or_else(|| {
+        let names: Vec<&str> = themes.themes.keys().map(String::as_str).collect();
+        format!("Unknown theme {}. Available: {}", theme, names.join(", "))
+    })?;
+    css_for_theme_with_class_style(selected, CLASS_STYLE).map_err(|e| e.to_string())
+}
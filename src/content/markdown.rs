@@ -0,0 +1,14 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// CommonMark plus the GFM extensions authors expect: tables, task lists,
+/// footnotes and strikethrough.
+pub fn to_html(source: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH;
+
+    let mut output = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut output, Parser::new_ext(source, options));
+    output
+}
@@ -0,0 +1,36 @@
+//! Turns the post body the author wrote into the HTML stored in
+//! `posts.content_html`. Runs once at save time, never per request.
+
+pub mod highlight;
+mod markdown;
+pub mod sanitize;
+
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::models::Blogs;
+
+pub const CONTENT_FORMATS: &[&str] = &["html", "markdown"];
+
+/// Renders `source` written in `format` (one of `CONTENT_FORMATS`) to HTML,
+/// runs it through the sanitizer and highlights code blocks. Markdown may
+/// contain raw HTML too, so both formats are cleaned. Highlighting comes
+/// last because its markup is generated here, not taken from the author.
+pub fn render(config: &Config, format: &str, source: &str) -> String {
+    let html = match format {
+        "markdown" => markdown::to_html(source),
+        _ => source.to_string(),
+    };
+    highlight::highlight_code_blocks(&sanitize::clean(config, &html))
+}
+
+/// Renders every post without `content_html`: posts saved before the
+/// sanitizer existed (cleared by migration 0010) and the seed posts. Runs at
+/// startup, before any request is served. Returns how many were rendered.
+pub async fn render_missing(pool: &PgPool, config: &Config) -> Result<usize, sqlx::Error> {
+    let posts = Blogs::find_unrendered(pool).await?;
+    for (id, format, source) in &posts {
+        Blogs::set_content_html(pool, *id, &render(config, format, source)).await?;
+    }
+    Ok(posts.len())
+}
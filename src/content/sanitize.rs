@@ -0,0 +1,206 @@
+use ammonia::Builder;
+use std::collections::HashSet;
+
+use crate::config::Config;
+
+const NOFOLLOW: &str = " rel=\"nofollow noopener\"";
+
+// ammonia removes these together with their content and panics if one is
+// also allowed, so SANITIZE_TAGS can never add them.
+const CLEAN_CONTENT_TAGS: &[&str] = &["script", "style"];
+// add_nofollow sets `rel` itself and expects links not to have one.
+const RESERVED_ATTRIBUTES: &[&str] = &["rel"];
+
+fn drop_reserved(setting: &str, items: Vec<String>, reserved: &[&str]) -> Vec<String> {
+    items
+        .into_iter()
+        .map(|item| item.to_ascii_lowercase())
+        .filter(|item| {
+            let allowed = !reserved.contains(&item.as_str());
+            if !allowed {
+                eprintln!("{}: ignoring \"{}\", it can't be allowed", setting, item);
+            }
+            allowed
+        })
+        .collect()
+}
+
+/// The `SANITIZE_TAGS` list without the tags that can't be allowed.
+pub fn allowed_tags(tags: Vec<String>) -> Vec<String> {
+    drop_reserved("SANITIZE_TAGS", tags, CLEAN_CONTENT_TAGS)
+}
+
+/// The `SANITIZE_ATTRIBUTES` list without the attributes that can't be allowed.
+pub fn allowed_attributes(attributes: Vec<String>) -> Vec<String> {
+    drop_reserved("SANITIZE_ATTRIBUTES", attributes, RESERVED_ATTRIBUTES)
+}
+
+/// Cleans post HTML with ammonia's default allow-list plus what the renderer
+/// needs (classes for highlighting, ids for footnotes, task list checkboxes)
+/// and the tags, attributes and URL schemes added in the config.
+pub fn clean(config: &Config, html: &str) -> String {
+    let url_schemes: HashSet<&str> = config.sanitize_url_schemes.iter().map(String::as_str).collect();
+
+    let cleaned = Builder::default()
+        .add_tags(["input"])
+        .add_tags(config.sanitize_tags.iter().map(String::as_str))
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_generic_attributes(["class", "id"])
+        .add_generic_attributes(config.sanitize_attributes.iter().map(String::as_str))
+        .url_schemes(url_schemes)
+        .link_rel(None)
+        .clean(html)
+        .to_string();
+
+    add_nofollow(&cleaned, site_host(&config.site_url))
+}
+
+fn site_host(site_url: &str) -> &str {
+    let without_scheme = site_url.split_once("://").map_or(site_url, |(_, rest)| rest);
+    without_scheme.split('/').next().unwrap_or_default()
+}
+
+fn is_external(href: &str, site_host: &str) -> bool {
+    let rest = match href.split_once("//") {
+        Some((scheme, rest)) if scheme.is_empty() || scheme.ends_with(':') => rest,
+        _ => return false,
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !host.eq_ignore_ascii_case(site_host)
+}
+
+/// Adds `rel="nofollow noopener"` to links pointing off the site. Works on
+/// ammonia's serialized output, where text never contains a raw `<`, every
+/// attribute value is double-quoted, and links have no `rel` of their own.
+fn add_nofollow(html: &str, site_host: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // The tag ends at the first '>' outside an attribute value.
+        let mut in_quotes = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                c == '>' && !in_quotes
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let tag = &rest[..end];
+        output.push_str(tag);
+
+        if tag.starts_with("<a ") {
+            let href = tag
+                .split_once(" href=\"")
+                .and_then(|(_, value)| value.split('"').next());
+            if href.is_some_and(|href| is_external(href, site_host)) {
+                output.push_str(NOFOLLOW);
+            }
+        }
+        rest = &rest[end..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::from_env();
+        config.site_url = "https://example.com".to_string();
+        config.sanitize_tags = Vec::new();
+        config.sanitize_attributes = Vec::new();
+        config.sanitize_url_schemes = vec!["http".into(), "https".into(), "mailto".into()];
+        config
+    }
+
+    #[test]
+    fn clean_removes_scripts_and_event_handlers() {
+        let html = clean(&config(), r#"<p onclick="x()">Hi<script>alert(1)</script></p><style>p{}</style>"#);
+        assert_eq!(html, "<p>Hi</p>");
+    }
+
+    #[test]
+    fn clean_drops_disallowed_url_schemes() {
+        let html = clean(&config(), r#"<a href="javascript:alert(1)">x</a>"#);
+        assert_eq!(html, "<a>x</a>");
+    }
+
+    #[test]
+    fn clean_keeps_classes_ids_and_task_list_checkboxes() {
+        let html = clean(
+            &config(),
+            r#"<span class="hl-k" id="fn-1">x</span><input type="checkbox" checked="" disabled="" name="n">"#,
+        );
+        assert_eq!(
+            html,
+            r#"<span class="hl-k" id="fn-1">x</span><input type="checkbox" checked="" disabled="">"#
+        );
+    }
+
+    #[test]
+    fn clean_adds_configured_tags_and_attributes() {
+        let mut config = config();
+        config.sanitize_tags = vec!["iframe".into()];
+        config.sanitize_attributes = vec!["data-x".into()];
+        let html = clean(&config, r#"<iframe data-x="1" src="https://a.test"></iframe>"#);
+        assert_eq!(html, r#"<iframe data-x="1"></iframe>"#);
+    }
+
+    #[test]
+    fn reserved_tags_and_attributes_are_never_allowed() {
+        assert_eq!(allowed_tags(vec!["SCRIPT".into(), "style".into(), "iframe".into()]), ["iframe"]);
+        assert_eq!(allowed_attributes(vec!["rel".into(), "data-x".into()]), ["data-x"]);
+
+        // Would panic inside ammonia if the filter let them through.
+        let mut config = config();
+        config.sanitize_tags = allowed_tags(vec!["script".into()]);
+        assert_eq!(clean(&config, "<script>x</script>ok"), "ok");
+    }
+
+    #[test]
+    fn clean_marks_only_external_links_nofollow() {
+        let html = clean(
+            &config(),
+            r#"<a href="https://other.test/x">a</a><a href="https://example.com/y">b</a><a href="/z">c</a>"#,
+        );
+        assert_eq!(
+            html,
+            r#"<a href="https://other.test/x" rel="nofollow noopener">a</a><a href="https://example.com/y">b</a><a href="/z">c</a>"#
+        );
+    }
+
+    #[test]
+    fn external_links_are_recognised() {
+        assert!(is_external("https://other.test", "example.com"));
+        assert!(is_external("//other.test/path", "example.com"));
+        assert!(is_external("http://EXAMPLE.com.evil.test/", "example.com"));
+        assert!(!is_external("https://EXAMPLE.com/path?q=1", "example.com"));
+        assert!(!is_external("/relative//path", "example.com"));
+        assert!(!is_external("#anchor", "example.com"));
+        assert!(!is_external("mailto:someone@other.test", "example.com"));
+    }
+
+    #[test]
+    fn add_nofollow_ignores_quoted_brackets_and_non_links() {
+        let html = r#"<img alt="a > b" src="https://other.test/i.png"><a title="x>y" href="https://other.test">l</a><abbr>t</abbr>"#;
+        assert_eq!(
+            add_nofollow(html, "example.com"),
+            r#"<img alt="a > b" src="https://other.test/i.png"><a title="x>y" href="https://other.test" rel="nofollow noopener">l</a><abbr>t</abbr>"#
+        );
+    }
+
+    #[test]
+    fn site_host_strips_scheme_and_path() {
+        assert_eq!(site_host("https://example.com/blog"), "example.com");
+        assert_eq!(site_host("localhost:8080"), "localhost:8080");
+    }
+}
@@ -0,0 +1,190 @@
+use actix_web::dev::ServiceResponse;
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::ErrorHandlerResponse;
+use actix_web::{HttpResponse, ResponseError};
+use askama::Template;
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::handlers::blogs::not_found_page;
+use crate::models::envelope::{ErrorEnvelope, ValidationErrors, CODE_EMPTY, CODE_ERROR};
+use crate::templates::blogs::ErrorTemplate;
+
+/// Machine-readable `error` of the API error envelope. Clients may rely on
+/// these: new codes can be added, existing ones keep their meaning.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthenticated,
+    Forbidden,
+    NotFound,
+    ValidationFailed,
+    RateLimited,
+    InternalError,
+}
+
+/// Everything a handler can fail with. The response is the JSON error
+/// envelope; outside `/api`, `html_error_page` swaps it for an HTML page.
+#[derive(Debug)]
+pub enum AppError {
+    // Malformed or contradictory request; the message says what's wrong.
+    BadRequest(String),
+    Unauthenticated,
+    Forbidden,
+    // What wasn't found, e.g. "Post not found".
+    NotFound(&'static str),
+    // One message per problem, by field where possible.
+    Validation(ValidationErrors),
+    // Too many requests; the client may try again after `retry_after`.
+    RateLimited { retry_after: Duration },
+    Database(sqlx::Error),
+    // Any other server-side failure; the message is only logged.
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::BadRequest(_) => ErrorCode::BadRequest,
+            AppError::Unauthenticated => ErrorCode::Unauthenticated,
+            AppError::Forbidden => ErrorCode::Forbidden,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Validation(_) => ErrorCode::ValidationFailed,
+            AppError::RateLimited { .. } => ErrorCode::RateLimited,
+            AppError::Database(_) | AppError::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn log(&self) {
+        match self {
+            AppError::Database(e) => eprintln!("Database error: {}", e),
+            AppError::Internal(message) => eprintln!("Internal error: {}", message),
+            _ => {}
+        }
+    }
+
+    fn retry_after(&self) -> Option<u64> {
+        match self {
+            // Rounded up, so retrying right on time never hits the limit.
+            AppError::RateLimited { retry_after } => {
+                Some(retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0))
+            }
+            _ => None,
+        }
+    }
+
+    /// The error page shown on the site.
+    pub fn html_response(&self) -> HttpResponse {
+        match self {
+            AppError::NotFound(_) => return not_found_page(),
+            // Only the admin needs a login; send the visitor there.
+            AppError::Unauthenticated => {
+                return HttpResponse::SeeOther()
+                    .insert_header((header::LOCATION, "/admin/login"))
+                    .finish();
+            }
+            _ => {}
+        }
+
+        let (title, message) = match self {
+            AppError::BadRequest(_) => ("Geçersiz İstek", "İstek anlaşılamadı."),
+            AppError::Unauthenticated => ("Giriş Gerekli", "Bu sayfayı görmek için giriş yapmalısınız."),
+            AppError::Forbidden => ("Erişim Engellendi", "Bu işlem için yetkiniz yok."),
+            AppError::Validation(_) => ("Geçersiz Veri", "Gönderilen bilgiler geçersiz."),
+            AppError::RateLimited { .. } => ("Çok Fazla İstek", "Lütfen biraz bekleyip tekrar deneyin."),
+            _ => ("Sunucu Hatası", "Bir şeyler ters gitti. Lütfen daha sonra tekrar deneyin."),
+        };
+        let status = self.status_code();
+        let template = ErrorTemplate { status: status.as_u16(), title, message };
+
+        let mut response = HttpResponse::build(status);
+        if let Some(seconds) = self.retry_after() {
+            response.insert_header((header::RETRY_AFTER, seconds));
+        }
+        response
+            .content_type("text/html; charset=utf-8")
+            .body(template.render().unwrap_or_else(|_| format!("{} - {}", status.as_u16(), title)))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::BadRequest(message) => write!(f, "{}", message),
+            AppError::Unauthenticated => write!(f, "Authentication required"),
+            AppError::Forbidden => write!(f, "You do not have permission to do this"),
+            AppError::NotFound(message) => write!(f, "{}", message),
+            AppError::Validation(_) => write!(f, "Validation failed"),
+            AppError::RateLimited { .. } => write!(f, "Too many requests, try again later"),
+            AppError::Database(_) | AppError::Internal(_) => write!(f, "Internal server error"),
+        }
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.log();
+
+        let data = match self {
+            AppError::Validation(errors) => Some(errors.clone()),
+            _ => None,
+        };
+        let envelope = ErrorEnvelope {
+            status: false,
+            code: if let AppError::NotFound(_) = self { CODE_EMPTY } else { CODE_ERROR },
+            message: self.to_string(),
+            error: self.code(),
+            data,
+        };
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let Some(seconds) = self.retry_after() {
+            response.insert_header((header::RETRY_AFTER, seconds));
+        }
+        response.json(envelope)
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        AppError::Database(e)
+    }
+}
+
+fn is_api_path(path: &str) -> bool {
+    path == "/api" || path.starts_with("/api/")
+}
+
+/// `ErrorHandlers` hook: outside `/api`, replaces the JSON body of an
+/// `AppError` with its HTML page. Other error responses pass through.
+pub fn html_error_page<B>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let page = match res.response().error().and_then(|e| e.as_error::<AppError>()) {
+        Some(error) if !is_api_path(res.request().path()) => error.html_response(),
+        _ => return Ok(ErrorHandlerResponse::Response(res.map_into_left_body())),
+    };
+
+    let (req, _) = res.into_parts();
+    Ok(ErrorHandlerResponse::Response(ServiceResponse::new(req, page).map_into_right_body()))
+}
+
+/// Logs an error that doesn't fail the request or task it happened in, such
+/// as best-effort cleanup or a background job, the same way a failed
+/// request's error is logged.
+pub fn log_ignored(error: impl Into<AppError>) {
+    error.into().log();
+}
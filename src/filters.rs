@@ -51,4 +51,17 @@ pub fn time_ago(date: &DateTime<Utc>) -> askama::Result<String> {
     };
 
     Ok(time_ago)
-}
\ No newline at end of file
+}
+
+pub fn status_label(status: &str) -> askama::Result<&'static str> {
+    Ok(match status {
+        "draft" => "Taslak",
+        "pending" => "Onay Bekliyor",
+        "scheduled" => "Zamanlandı",
+        "published" => "Yayında",
+        "approved" => "Onaylı",
+        "rejected" => "Reddedildi",
+        "spam" => "Spam",
+        _ => "Bilinmiyor",
+    })
+}
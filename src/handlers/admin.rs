@@ -0,0 +1,529 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use askama::Template;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::auth::{self, AuthenticatedUser, SESSION_COOKIE};
+use crate::error::{self, AppError};
+use crate::models::api::{Api, COMMENT_STATUSES};
+use crate::models::api_tokens::{ApiToken, NewToken};
+use crate::models::blogs::{Blogs, Category, PostForm, PostInput, POST_STATUSES};
+use crate::models::sessions::Session;
+use crate::models::users::User;
+use crate::permissions::Permission;
+use crate::state::AppState;
+use crate::templates::admin::{
+    CommentsTemplate, DashboardTemplate, LoginTemplate, PostFormTemplate, PostsTemplate, TokensTemplate,
+};
+
+#[derive(Deserialize)]
+pub struct LoginForm {
+    username: String,
+    password: String,
+}
+
+fn redirect(location: &str) -> HttpResponse {
+    HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, location))
+        .finish()
+}
+
+fn render_with<T: Template>(mut response: actix_web::HttpResponseBuilder, template: T) -> HttpResponse {
+    let html = template.render().unwrap();
+    response
+        .content_type("text/html; charset=utf-8")
+        .body(html)
+}
+
+fn render<T: Template>(template: T) -> HttpResponse {
+    render_with(HttpResponse::Ok(), template)
+}
+
+pub async fn login_page(user: Option<AuthenticatedUser>) -> Result<HttpResponse, AppError> {
+    if user.is_some() {
+        return Ok(redirect("/admin"));
+    }
+
+    let template = LoginTemplate {
+        username: String::new(),
+        error: None,
+    };
+    Ok(render(template))
+}
+
+pub async fn login(
+    data: web::Data<AppState>,
+    form: web::Form<LoginForm>,
+) -> Result<HttpResponse, AppError> {
+    let form = form.into_inner();
+    let login = form.username.trim().to_string();
+
+    let user = User::find_by_login(&data.pool, &login).await?;
+
+    let user = match auth::authenticate(user, form.password).await {
+        Some(user) => user,
+        None => {
+            let template = LoginTemplate {
+                username: login,
+                error: Some("Kullanıcı adı veya şifre hatalı".to_string()),
+            };
+            return Ok(render_with(HttpResponse::Unauthorized(), template));
+        }
+    };
+
+    if let Err(e) = Session::delete_expired(&data.pool).await {
+        error::log_ignored(e);
+    }
+
+    let token = Session::create(&data.pool, user.id, data.config.session_ttl).await?;
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, "/admin"))
+        .cookie(auth::session_cookie(&data.config, token))
+        .finish())
+}
+
+pub async fn logout(req: HttpRequest, data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    if let Some(cookie) = req.cookie(SESSION_COOKIE) {
+        if let Err(e) = Session::delete(&data.pool, cookie.value()).await {
+            error::log_ignored(e);
+        }
+    }
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, "/admin/login"))
+        .cookie(auth::removal_cookie(&data.config))
+        .finish())
+}
+
+pub async fn dashboard(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let post_counts = Blogs::count_by_status(&data.pool).await?;
+
+    let comment_counts = if auth.role.has(Permission::ModerateComments) {
+        Api::count_by_status(&data.pool).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(render(DashboardTemplate {
+        user: auth.user,
+        post_counts,
+        comment_counts,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct NoticeQuery {
+    notice: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct StatusForm {
+    status: String,
+}
+
+fn notice_text(notice: &str) -> Option<String> {
+    let text = match notice {
+        "created" => "Yazı oluşturuldu",
+        "updated" => "Yazı güncellendi",
+        "deleted" => "Yazı silindi",
+        "status" => "Yazının durumu güncellendi",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+pub async fn posts_list(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    query: web::Query<NoticeQuery>,
+) -> Result<HttpResponse, AppError> {
+    // Authors and contributors only see their own posts.
+    let author_filter = if auth.role.has(Permission::EditOthersPosts) {
+        None
+    } else {
+        Some(auth.user.id)
+    };
+
+    let posts = Blogs::find_admin_posts(&data.pool, author_filter).await?;
+
+    let notice = query.notice.as_deref().and_then(notice_text);
+    Ok(render(PostsTemplate {
+        user: auth.user,
+        role: auth.role,
+        posts,
+        notice,
+    }))
+}
+
+async fn render_post_form(
+    data: &AppState,
+    auth: AuthenticatedUser,
+    post_id: Option<i32>,
+    form: PostForm,
+    errors: Vec<String>,
+) -> Result<HttpResponse, AppError> {
+    let categories = Category::find_all(&data.pool).await?;
+
+    let status = if errors.is_empty() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::UnprocessableEntity()
+    };
+
+    let template = PostFormTemplate {
+        statuses: auth.role.allowed_statuses(),
+        user: auth.user,
+        post_id,
+        form,
+        categories,
+        errors,
+    };
+    Ok(render_with(status, template))
+}
+
+pub async fn new_post(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+) -> Result<HttpResponse, AppError> {
+    let form = PostForm {
+        status: "draft".to_string(),
+        content_format: "markdown".to_string(),
+        ..PostForm::default()
+    };
+    render_post_form(&data, auth, None, form, Vec::new()).await
+}
+
+// Validation plus the checks that need the database or the current user:
+// a unique slug and permission to put the post into the chosen status.
+pub async fn validate_post(
+    data: &AppState,
+    auth: &AuthenticatedUser,
+    form: &PostForm,
+    post_id: Option<i32>,
+    author_id: i32,
+) -> Result<Result<PostInput, Vec<String>>, sqlx::Error> {
+    let input = match form.validate(&data.config) {
+        Ok(input) => input,
+        Err(errors) => return Ok(Err(errors)),
+    };
+
+    if !auth.can_set_status(author_id, &input.status) {
+        return Ok(Err(vec!["Yazıyı bu duruma almak için yetkiniz yok".to_string()]));
+    }
+
+    if Blogs::slug_taken(&data.pool, &input.slug, post_id).await? {
+        return Ok(Err(vec![format!("\"{}\" kısa adı başka bir yazıda kullanılıyor", input.slug)]));
+    }
+
+    Ok(Ok(input))
+}
+
+pub async fn create_post(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    fields: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, AppError> {
+    let form = PostForm::from_pairs(fields.into_inner());
+
+    let input = match validate_post(&data, &auth, &form, None, auth.user.id).await? {
+        Ok(input) => input,
+        Err(errors) => return render_post_form(&data, auth, None, form, errors).await,
+    };
+
+    Blogs::create_post(&data.pool, &input, auth.user.id).await?;
+    data.invalidate_posts();
+    Ok(redirect("/admin/posts?notice=created"))
+}
+
+pub async fn edit_post(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    post_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let post_id = post_id.into_inner();
+
+    let post = Blogs::find_post_by_id(&data.pool, post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+
+    if !auth.can_edit_post(&post) {
+        return Err(AppError::Forbidden);
+    }
+
+    let category_ids = Category::find_ids_for_post(&data.pool, post_id).await?;
+
+    let form = PostForm::from_post(&post, category_ids);
+    render_post_form(&data, auth, Some(post_id), form, Vec::new()).await
+}
+
+pub async fn update_post(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    post_id: web::Path<i32>,
+    fields: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, AppError> {
+    let post_id = post_id.into_inner();
+    let form = PostForm::from_pairs(fields.into_inner());
+
+    let existing = Blogs::find_post_by_id(&data.pool, post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+
+    if !auth.can_edit_post(&existing) {
+        return Err(AppError::Forbidden);
+    }
+
+    let input = match validate_post(&data, &auth, &form, Some(post_id), existing.author_id).await? {
+        Ok(input) => input,
+        Err(errors) => return render_post_form(&data, auth, Some(post_id), form, errors).await,
+    };
+
+    Blogs::update_post(&data.pool, post_id, &input)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+    data.invalidate_posts();
+    Ok(redirect("/admin/posts?notice=updated"))
+}
+
+pub async fn change_post_status(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    post_id: web::Path<i32>,
+    form: web::Form<StatusForm>,
+) -> Result<HttpResponse, AppError> {
+    let post_id = post_id.into_inner();
+
+    if !POST_STATUSES.contains(&form.status.as_str()) {
+        return Err(AppError::BadRequest("Invalid status".to_string()));
+    }
+
+    let existing = Blogs::find_post_by_id(&data.pool, post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+
+    if !auth.can_edit_post(&existing) || !auth.can_set_status(existing.author_id, &form.status) {
+        return Err(AppError::Forbidden);
+    }
+
+    // Scheduling needs a future date, which only the edit form can set.
+    if form.status == "scheduled" && existing.published_at.is_none_or(|date| date <= Utc::now()) {
+        return Err(AppError::BadRequest("Scheduling requires a future publish date".to_string()));
+    }
+
+    Blogs::set_status(&data.pool, post_id, &form.status)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+    data.invalidate_posts();
+    Ok(redirect("/admin/posts?notice=status"))
+}
+
+pub async fn delete_post(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    post_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let post_id = post_id.into_inner();
+
+    let existing = Blogs::find_post_by_id(&data.pool, post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+    if !auth.can_edit_post(&existing) {
+        return Err(AppError::Forbidden);
+    }
+
+    Blogs::delete_post(&data.pool, post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+    data.invalidate_posts();
+    Ok(redirect("/admin/posts?notice=deleted"))
+}
+
+#[derive(Deserialize)]
+pub struct CommentsQuery {
+    status: Option<String>,
+    notice: Option<String>,
+}
+
+pub async fn comments_queue(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    query: web::Query<CommentsQuery>,
+) -> Result<HttpResponse, AppError> {
+    auth.require(Permission::ModerateComments)?;
+
+    let status = query
+        .status
+        .as_deref()
+        .filter(|s| COMMENT_STATUSES.contains(s))
+        .unwrap_or("pending")
+        .to_string();
+
+    let comments = Api::find_for_moderation(&data.pool, &status).await?;
+
+    let counts = Api::count_by_status(&data.pool).await?;
+
+    // Only ever a count from comments_bulk; anything else is ignored.
+    let notice = query
+        .notice
+        .as_deref()
+        .and_then(|n| n.parse::<u64>().ok())
+        .map(|n| format!("{} yorum güncellendi", n));
+    Ok(render(CommentsTemplate {
+        user: auth.user,
+        status,
+        counts,
+        comments,
+        notice,
+    }))
+}
+
+// Applies one moderation action to every checked comment. The form posts
+// `action`, the status tab to return to, and one `ids` field per comment.
+pub async fn comments_bulk(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    fields: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, AppError> {
+    auth.require(Permission::ModerateComments)?;
+
+    let mut action = String::new();
+    let mut back_to = String::from("pending");
+    let mut ids: Vec<i32> = Vec::new();
+    for (key, value) in fields.into_inner() {
+        match key.as_str() {
+            "action" => action = value,
+            "status" => back_to = value,
+            "ids" => ids.extend(value.parse::<i32>().ok()),
+            _ => {}
+        }
+    }
+
+    let result = match action.as_str() {
+        "approve" => Api::set_status(&data.pool, &ids, "approved").await,
+        "reject" => Api::set_status(&data.pool, &ids, "rejected").await,
+        "spam" => Api::set_status(&data.pool, &ids, "spam").await,
+        "pending" => Api::set_status(&data.pool, &ids, "pending").await,
+        "delete" => Api::delete(&data.pool, &ids).await,
+        _ => return Err(AppError::BadRequest("Invalid action".to_string())),
+    };
+
+    if !COMMENT_STATUSES.contains(&back_to.as_str()) {
+        back_to = "pending".to_string();
+    }
+
+    let changed = result?;
+    Ok(redirect(&format!("/admin/comments?status={}&notice={}", back_to, changed)))
+}
+
+async fn render_tokens(
+    data: &AppState,
+    auth: AuthenticatedUser,
+    new_token: Option<String>,
+    errors: Vec<String>,
+    notice: Option<String>,
+) -> Result<HttpResponse, AppError> {
+    let tokens = ApiToken::find_for_user(&data.pool, auth.user.id).await?;
+
+    let status = if errors.is_empty() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::UnprocessableEntity()
+    };
+
+    Ok(render_with(status, TokensTemplate {
+        scopes: auth.role.allowed_scopes(),
+        user: auth.user,
+        tokens,
+        new_token,
+        errors,
+        notice,
+    }))
+}
+
+pub async fn tokens_page(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    query: web::Query<NoticeQuery>,
+) -> Result<HttpResponse, AppError> {
+    let notice = match query.notice.as_deref() {
+        Some("deleted") => Some("Anahtar silindi".to_string()),
+        _ => None,
+    };
+    render_tokens(&data, auth, None, Vec::new(), notice).await
+}
+
+// The form posts `name`, `expires_in_days` (empty for no expiry) and one
+// `scopes` field per checked scope.
+pub async fn create_token(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    fields: web::Form<Vec<(String, String)>>,
+) -> Result<HttpResponse, AppError> {
+    let mut name = String::new();
+    let mut expires_in_days = String::new();
+    let mut scopes: Vec<String> = Vec::new();
+    for (key, value) in fields.into_inner() {
+        match key.as_str() {
+            "name" => name = value.trim().to_string(),
+            "expires_in_days" => expires_in_days = value,
+            "scopes" => scopes.push(value),
+            _ => {}
+        }
+    }
+
+    let mut errors = Vec::new();
+    if name.is_empty() {
+        errors.push("Anahtar için bir ad girin".to_string());
+    } else if name.chars().count() > 100 {
+        errors.push("Ad en fazla 100 karakter olabilir".to_string());
+    }
+
+    let allowed = auth.role.allowed_scopes();
+    if scopes.is_empty() {
+        errors.push("En az bir yetki seçin".to_string());
+    } else if scopes.iter().any(|scope| !allowed.contains(&scope.as_str())) {
+        errors.push("Bu yetkiyi verme izniniz yok".to_string());
+    }
+
+    let expires_at = match expires_in_days.trim() {
+        "" => None,
+        days => match days.parse::<u64>() {
+            Ok(days) if (1..=3650).contains(&days) => Some(Utc::now() + chrono::Days::new(days)),
+            _ => {
+                errors.push("Geçersiz geçerlilik süresi".to_string());
+                None
+            }
+        },
+    };
+
+    if !errors.is_empty() {
+        return render_tokens(&data, auth, None, errors, None).await;
+    }
+
+    let token = NewToken::generate();
+    let secret = token.secret.clone();
+    // Hashed like a password, on the blocking pool.
+    let token_hash = match web::block(move || auth::hash_password(&secret)).await {
+        Ok(Ok(hash)) => hash,
+        _ => return Err(AppError::Internal("Could not hash API token".to_string())),
+    };
+
+    ApiToken::create(&data.pool, auth.user.id, &name, &token.prefix, &token_hash, &scopes, expires_at).await?;
+
+    render_tokens(&data, auth, Some(token.value()), Vec::new(), None).await
+}
+
+pub async fn delete_token(
+    data: web::Data<AppState>,
+    auth: AuthenticatedUser,
+    token_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    if !ApiToken::delete(&data.pool, token_id.into_inner(), auth.user.id).await? {
+        return Err(AppError::NotFound("Token not found"));
+    }
+    Ok(redirect("/admin/tokens?notice=deleted"))
+}
@@ -1,72 +1,752 @@
-use actix_web::{web, HttpResponse, Result};
-use serde::Deserialize;
-use sqlx::PgPool;
-use serde_json::json;
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
+use chrono::{Days, NaiveDate, NaiveTime};
+use serde::{Deserialize, Deserializer};
+use utoipa::openapi::{schema::Type, Object, ObjectBuilder};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-use crate::models::api::Api;
-#[derive(Deserialize)]
+use crate::auth::{ApiReader, AuthenticatedUser, TokenUser};
+use crate::error::AppError;
+use crate::handlers::admin::validate_post;
+use crate::models::api::{Api, CommentNode, COMMENT_MAX_LENGTH, COMMENT_STATUSES};
+use crate::models::blogs::{
+    BlogWithAuthor, Blogs, Category, CategoryWithCount, PostFilter, PostForm, PostPayload,
+    POST_STATUSES,
+};
+use crate::models::envelope::{Envelope, ErrorEnvelope, MessageEnvelope, PageInfo, PostList, ValidationErrors};
+use crate::models::pagination::Pagination;
+use crate::models::popular::{PopularPost, PopularQuery};
+use crate::models::users::AuthorProfile;
+use crate::permissions::Permission;
+use crate::models::search::{Search, SearchQuery, SearchResult};
+use crate::state::AppState;
+use crate::views;
+
+#[derive(Deserialize, Validate, ToSchema)]
 pub struct CommentData {
     post_id: i32,
+    parent_id: Option<i32>,
+    /// Shown with the comment. Surrounding whitespace is removed.
+    #[serde(deserialize_with = "trimmed")]
+    #[validate(length(min = 1, max = 100, message = "Ad boş olamaz ve en fazla 100 karakter olabilir"))]
+    #[schema(min_length = 1, max_length = 100)]
     username: String,
+    /// Only shown to moderators. Blank counts as missing.
+    #[serde(default, deserialize_with = "trimmed_option")]
+    #[validate(
+        email(message = "Geçerli bir e-posta adresi girin"),
+        length(max = 255, message = "E-posta adresi en fazla 255 karakter olabilir")
+    )]
+    #[schema(max_length = 255)]
+    author_email: Option<String>,
+    #[serde(deserialize_with = "trimmed")]
+    #[validate(length(min = 1, max = COMMENT_MAX_LENGTH, message = "Yorum boş olamaz ve izin verilenden uzun olamaz"))]
+    #[schema(schema_with = comment_content_schema)]
     content: String,
 }
 
+// `#[schema(max_length)]` only takes a literal, so this keeps the spec in step
+// with COMMENT_MAX_LENGTH.
+fn comment_content_schema() -> Object {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .min_length(Some(1))
+        .max_length(Some(COMMENT_MAX_LENGTH as usize))
+        .build()
+}
+
+fn trimmed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|value| value.trim().to_string())
+}
+
+fn trimmed_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty()))
+}
+
 // cache will be added in future versions
 
 
+/// Approved comments of a post, as a reply tree.
+#[utoipa::path(
+    get,
+    path = "/api/comments/get/{post_id}",
+    tag = "comments",
+    params(("post_id" = i32, Path, description = "Post id")),
+    responses(
+        (status = 200, description = "Comments, or `code` 2 and an empty list", body = Envelope<Vec<CommentNode>>),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
 pub async fn comments(
-    pool: web::Data<PgPool>,
+    data: web::Data<AppState>,
     post_id: web::Path<i32>
-) -> Result<HttpResponse, actix_web::Error> {
-    let posts = match Api::find_comments(&pool, &post_id).await {
-        Ok(posts) => posts,
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            return Ok(HttpResponse::InternalServerError().finish());
-        }
-    };
+) -> Result<HttpResponse, AppError> {
+    let posts = Api::find_comments(&data.pool, &post_id, data.config.comment_max_depth).await?;
 
     if posts.is_empty() {
-        return Ok(HttpResponse::Ok().json(json!({
-            "status": false,
-            "code": 2,
-            "message": "No comments found",
-            "data": []
-        })));
+        return Ok(HttpResponse::Ok().json(Envelope::empty("No comments found", posts)));
     }
 
-    Ok(HttpResponse::Ok().json(json!({
-        "status": true,
-        "code": 1,
-        "message": "Comments found successfully",
-        "data": posts
-    })))
+    Ok(HttpResponse::Ok().json(Envelope::found("Comments found successfully", posts)))
+}
+
+/// Malformed JSON bodies get the envelope too.
+pub fn json_error(err: actix_web::error::JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::BadRequest(format!("Invalid JSON body: {}", err)).into()
+}
+
+/// Malformed query strings (`?page=abc`) get the JSON envelope instead of
+/// actix's plain text error.
+pub fn query_error(err: actix_web::error::QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::BadRequest(format!("Invalid query string: {}", err)).into()
 }
+
+/// Submits a comment; it is shown once a moderator approves it. The post
+/// must be published and still open for comments.
+#[utoipa::path(
+    post,
+    path = "/api/comments/add",
+    tag = "comments",
+    request_body = CommentData,
+    responses(
+        (status = 200, description = "The stored comment", body = Envelope<Api>),
+        (status = 400, description = "Malformed body", body = ErrorEnvelope),
+        (status = 422, description = "`data.fields` lists the problems by field", body = ErrorEnvelope),
+        (status = 429, description = "Too many comments from this address; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
 pub async fn addcomment(
-    pool: web::Data<PgPool>,
+    req: HttpRequest,
+    data: web::Data<AppState>,
     comment_data: web::Json<CommentData>,
-) -> Result<HttpResponse, actix_web::Error> {
-    let post = match Api::add_comment(
-        &pool,
+) -> Result<HttpResponse, AppError> {
+    data.comment_limiter
+        .check(&views::client_ip(&req, data.config.trust_proxy))
+        .await?;
+
+    let mut errors: ValidationErrors = comment_data.validate().err().map(Into::into).unwrap_or_default();
+
+    match Api::accepts_comments(&data.pool, comment_data.post_id, data.config.comments_close_after_days).await? {
+        Some(true) => {}
+        Some(false) => errors.add("post_id", "Bu yazı yeni yorumlara kapalı"),
+        None => errors.add("post_id", "Yazı bulunamadı"),
+    }
+
+    if let Some(parent_id) = comment_data.parent_id {
+        match Api::find_reply_target(&data.pool, parent_id).await? {
+            Some(target) if target.post_id != Some(comment_data.post_id) || target.status != "approved" => {
+                errors.add("parent_id", "Yanıtlanan yorum bu yazıya ait değil");
+            }
+            Some(target) if target.depth as usize + 1 >= data.config.comment_max_depth => {
+                errors.add("parent_id", "Bu yoruma daha fazla yanıt verilemez");
+            }
+            Some(_) => {}
+            None => errors.add("parent_id", "Yanıtlanan yorum bulunamadı"),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    let post = Api::add_comment(
+        &data.pool,
         &comment_data.post_id,
+        comment_data.parent_id,
         &comment_data.username,
+        comment_data.author_email.as_deref(),
         &comment_data.content
-    ).await {
-        Ok(post) => post,
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(json!({
-                "status": false,
-                "code": 0,
-                "message": "Internal server error"
-            })));
+    ).await?;
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Comment submitted for moderation", post)))
+}
+
+/// Full-text search over published posts.
+#[utoipa::path(
+    get,
+    path = "/api/posts/search",
+    tag = "posts",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matches by relevance, or `code` 2 and an empty list", body = Envelope<Vec<SearchResult>>),
+        (status = 400, description = "Missing search query", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
+pub async fn search_posts(
+    data: web::Data<AppState>,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, AppError> {
+    let terms = query.terms();
+    if terms.is_empty() {
+        return Err(AppError::BadRequest("Missing search query".to_string()));
+    }
+
+    let results = Search::posts(&data.pool, &data.config.search_config, terms, query.limit()).await?;
+
+    if results.is_empty() {
+        return Ok(HttpResponse::Ok().json(Envelope::empty("No posts found", results)));
+    }
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Posts found successfully", results)))
+}
+
+/// Most viewed posts of all time or of the last few days.
+#[utoipa::path(
+    get,
+    path = "/api/posts/popular",
+    tag = "posts",
+    params(PopularQuery),
+    responses(
+        (status = 200, description = "Posts by views, or `code` 2 and an empty list", body = Envelope<Vec<PopularPost>>),
+        (status = 400, description = "Invalid window", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
+pub async fn popular_posts(
+    data: web::Data<AppState>,
+    query: web::Query<PopularQuery>,
+) -> Result<HttpResponse, AppError> {
+    let Some(window) = query.window() else {
+        return Err(AppError::BadRequest("Invalid window, expected \"all\" or a number of days like \"7d\"".to_string()));
+    };
+
+    let posts = data.popular_posts(window).await?;
+
+    if posts.is_empty() {
+        return Ok(HttpResponse::Ok().json(Envelope::empty("No posts found", posts)));
+    }
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Posts found successfully", posts)))
+}
+
+pub const MAX_PER_PAGE: i64 = 100;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PostListQuery {
+    /// Page number, starting at 1.
+    pub page: Option<i64>,
+    /// At most 100, default `POSTS_PER_PAGE`.
+    pub per_page: Option<i64>,
+    /// Category slug.
+    pub category: Option<String>,
+    /// Author username.
+    pub author: Option<String>,
+    /// `published` (default), `draft`, `pending` or `scheduled`; anything
+    /// but `published` needs a staff session.
+    pub status: Option<String>,
+    /// First publication date, `YYYY-MM-DD` in UTC.
+    pub from: Option<NaiveDate>,
+    /// Last publication date, inclusive.
+    pub to: Option<NaiveDate>,
+}
+
+/// The request's path and query string without `page`, for pagination links.
+fn listing_base_path(req: &HttpRequest) -> String {
+    let params: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|param| !param.is_empty() && !param.starts_with("page="))
+        .collect();
+    if params.is_empty() {
+        req.path().to_string()
+    } else {
+        format!("{}?{}", req.path(), params.join("&"))
+    }
+}
+
+fn empty_posts(pagination: &Pagination) -> HttpResponse {
+    HttpResponse::Ok().json(Envelope::empty(
+        "No posts found",
+        PostList { posts: Vec::new(), pagination: PageInfo::from(pagination) },
+    ))
+}
+
+/// Shared by `/api/posts` and `/api/categories/{slug}/posts`; `category` is
+/// the already resolved path category, if any.
+async fn filtered_posts(
+    req: &HttpRequest,
+    data: &AppState,
+    query: &PostListQuery,
+    user: Option<AuthenticatedUser>,
+    category: Option<Category>,
+) -> Result<HttpResponse, AppError> {
+    let per_page = query.per_page.unwrap_or(data.config.posts_per_page).clamp(1, MAX_PER_PAGE);
+    let mut pagination = Pagination::new(query.page.unwrap_or(1), per_page, 0, listing_base_path(req));
+    let mut filter = PostFilter::default();
+
+    match (category, query.category.as_deref()) {
+        (Some(category), _) => filter.category_id = Some(category.id),
+        (None, Some(slug)) => match Category::find_by_slug(&data.pool, slug).await? {
+            Some(category) => filter.category_id = Some(category.id),
+            None => return Ok(empty_posts(&pagination)),
+        },
+        (None, None) => {}
+    }
+
+    if let Some(username) = query.author.as_deref() {
+        match AuthorProfile::find_by_username(&data.pool, username).await? {
+            Some(author) => filter.author_id = Some(author.id),
+            None => return Ok(empty_posts(&pagination)),
+        }
+    }
+
+    match query.status.as_deref() {
+        None | Some("published") => {}
+        Some(status) if POST_STATUSES.contains(&status) => {
+            let Some(user) = user else {
+                return Err(AppError::Unauthenticated);
+            };
+            // Without EditOthersPosts, unpublished posts are limited to your own.
+            if !user.role.has(Permission::EditOthersPosts) {
+                if filter.author_id.is_some_and(|id| id != user.user.id) {
+                    return Err(AppError::Forbidden);
+                }
+                filter.author_id = Some(user.user.id);
+            }
+            filter.status = Some(status.to_string());
+        }
+        Some(_) => return Err(AppError::BadRequest("Unknown status".to_string())),
+    }
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest("\"from\" must not be after \"to\"".to_string()));
         }
+    }
+    filter.published_from = query.from.map(|date| date.and_time(NaiveTime::MIN).and_utc());
+    filter.published_until = query
+        .to
+        .and_then(|date| date.checked_add_days(Days::new(1)))
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc());
+
+    pagination.total = Blogs::count_filtered(&data.pool, &filter).await?;
+    if pagination.is_out_of_range() {
+        return Err(AppError::NotFound("Page not found"));
+    }
+    if pagination.total == 0 {
+        return Ok(empty_posts(&pagination));
+    }
+
+    let posts = Blogs::find_filtered(&data.pool, &filter, pagination.per_page, pagination.offset()).await?;
+
+    let mut response = HttpResponse::Ok();
+    if let Some(link) = pagination.link_header() {
+        response.insert_header((header::LINK, link));
+    }
+    Ok(response.json(Envelope::found(
+        "Posts found successfully",
+        PostList { posts, pagination: PageInfo::from(&pagination) },
+    )))
+}
+
+/// Published posts, newest first, with optional filters.
+#[utoipa::path(
+    get,
+    path = "/api/posts",
+    tag = "posts",
+    params(PostListQuery),
+    responses(
+        (status = 200, description = "A page of posts, or `code` 2 and an empty page; sends `Link` headers", body = Envelope<PostList>),
+        (status = 400, description = "Unknown status, bad date range or malformed query", body = ErrorEnvelope),
+        (status = 401, description = "`status` other than `published` without a session or a `posts:read`/`posts:write` token, or an invalid token", body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, description = "Page past the end", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security((), ("session" = []), ("api_token" = []))
+)]
+pub async fn list_posts(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    query: web::Query<PostListQuery>,
+    reader: ApiReader,
+) -> Result<HttpResponse, AppError> {
+    filtered_posts(&req, &data, &query, reader.0, None).await
+}
+
+/// A published post with its categories and related posts.
+#[utoipa::path(
+    get,
+    path = "/api/posts/{slug}",
+    tag = "posts",
+    params(("slug" = String, Path, description = "Post slug")),
+    responses(
+        (status = 200, body = Envelope<BlogWithAuthor>),
+        (status = 404, body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
+pub async fn post_by_slug(
+    data: web::Data<AppState>,
+    slug: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let post = match data.post_cache.get(slug.as_str()).await {
+        Some(post) => post,
+        None => match Blogs::find_post_by_slug(&data.pool, &slug, data.config.related_posts_limit).await? {
+            Some(post) => {
+                data.post_cache.insert(slug.to_string(), post.clone()).await;
+                post
+            }
+            None => return Err(AppError::NotFound("Post not found")),
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Post found successfully", post)))
+}
+
+/// Every category with its number of published posts.
+#[utoipa::path(
+    get,
+    path = "/api/categories",
+    tag = "categories",
+    responses(
+        (status = 200, description = "Categories, or `code` 2 and an empty list", body = Envelope<Vec<CategoryWithCount>>),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
+pub async fn categories(data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let categories = Category::find_all_with_counts(&data.pool).await?;
+
+    if categories.is_empty() {
+        return Ok(HttpResponse::Ok().json(Envelope::empty("No categories found", categories)));
+    }
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Categories found successfully", categories)))
+}
+
+/// Posts of one category; takes the same filters as `/api/posts`.
+#[utoipa::path(
+    get,
+    path = "/api/categories/{slug}/posts",
+    tag = "categories",
+    params(("slug" = String, Path, description = "Category slug"), PostListQuery),
+    responses(
+        (status = 200, description = "A page of posts, or `code` 2 and an empty page; sends `Link` headers", body = Envelope<PostList>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, description = "Unknown category or page past the end", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security((), ("session" = []), ("api_token" = []))
+)]
+pub async fn category_posts(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    slug: web::Path<String>,
+    query: web::Query<PostListQuery>,
+    reader: ApiReader,
+) -> Result<HttpResponse, AppError> {
+    let category = Category::find_by_slug(&data.pool, &slug)
+        .await?
+        .ok_or(AppError::NotFound("Category not found"))?;
+
+    filtered_posts(&req, &data, &query, reader.0, Some(category)).await
+}
+
+/// Public profile of an author.
+#[utoipa::path(
+    get,
+    path = "/api/authors/{username}",
+    tag = "authors",
+    params(("username" = String, Path)),
+    responses(
+        (status = 200, body = Envelope<AuthorProfile>),
+        (status = 404, body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    )
+)]
+pub async fn author(
+    data: web::Data<AppState>,
+    username: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let author = AuthorProfile::find_by_username(&data.pool, &username)
+        .await?
+        .ok_or(AppError::NotFound("Author not found"))?;
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Author found successfully", author)))
+}
+
+/// Validates `form` and saves it as a new post (`post_id` None) or over an
+/// existing one, then drops the cached pages.
+async fn save_post(
+    data: &AppState,
+    user: &TokenUser,
+    form: PostForm,
+    existing: Option<&Blogs>,
+) -> Result<HttpResponse, AppError> {
+    let post_id = existing.and_then(|post| post.id);
+    let author_id = existing.map_or(user.auth.user.id, |post| post.author_id);
+
+    let input = validate_post(data, &user.auth, &form, post_id, author_id)
+        .await?
+        .map_err(|errors| AppError::Validation(errors.into()))?;
+
+    let saved = match post_id {
+        Some(id) => Blogs::update_post(&data.pool, id, &input).await?,
+        None => Some(Blogs::create_post(&data.pool, &input, author_id).await?),
+    };
+    let post = saved.ok_or(AppError::NotFound("Post not found"))?;
+
+    data.invalidate_posts();
+
+    let mut response = if existing.is_some() {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::Created()
+    };
+    Ok(response
+        .insert_header((header::LOCATION, format!("/api/posts/{}", post.slug)))
+        .json(Envelope::found(if existing.is_some() { "Post updated" } else { "Post created" }, post)))
+}
+
+/// Applies the payload to `form`; unknown category slugs are validation errors.
+async fn apply_payload(data: &AppState, payload: PostPayload, form: &mut PostForm) -> Result<(), AppError> {
+    let categories = Category::find_all(&data.pool).await?;
+    payload.apply_to(form, &categories).map_err(|errors| AppError::Validation(errors.into()))
+}
+
+fn new_post_form() -> PostForm {
+    PostForm {
+        status: "draft".to_string(),
+        content_format: "markdown".to_string(),
+        ..PostForm::default()
+    }
+}
+
+/// Creates a post; it is a markdown draft unless the body says otherwise.
+#[utoipa::path(
+    post,
+    path = "/api/posts",
+    tag = "posts",
+    request_body = PostPayload,
+    responses(
+        (status = 201, description = "Created; `Location` points at the post", body = Envelope<Blogs>),
+        (status = 400, description = "Malformed JSON", body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, description = "Missing `posts:write` scope or permission", body = ErrorEnvelope),
+        (status = 422, description = "`data.errors` lists the problems", body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn create_post(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    payload: web::Json<PostPayload>,
+) -> Result<HttpResponse, AppError> {
+    limit_writes(&data, &user).await?;
+    user.require_scope("posts:write")?;
+    user.auth.require(Permission::EditOwnPosts)?;
+
+    let mut form = new_post_form();
+    apply_payload(&data, payload.into_inner(), &mut form).await?;
+    save_post(&data, &user, form, None).await
+}
+
+/// Counts a write API request against the user's `API_WRITE_RATE_LIMIT`.
+async fn limit_writes(data: &AppState, user: &TokenUser) -> Result<(), AppError> {
+    data.write_limiter.check(&user.auth.user.id.to_string()).await
+}
+
+/// Loads a post the token's user may edit.
+async fn editable_post(data: &AppState, user: &TokenUser, post_id: i32) -> Result<Blogs, AppError> {
+    match Blogs::find_post_by_id(&data.pool, post_id).await? {
+        Some(post) if user.auth.can_edit_post(&post) => Ok(post),
+        Some(_) => Err(AppError::Forbidden),
+        None => Err(AppError::NotFound("Post not found")),
+    }
+}
+
+/// PUT replaces every field (missing ones fall back to the defaults of a
+/// new post); PATCH only changes the fields that are present.
+async fn update_post(
+    data: &AppState,
+    user: TokenUser,
+    post_id: i32,
+    payload: PostPayload,
+    partial: bool,
+) -> Result<HttpResponse, AppError> {
+    limit_writes(data, &user).await?;
+    user.require_scope("posts:write")?;
+
+    let existing = editable_post(data, &user, post_id).await?;
+
+    let mut form = if partial {
+        PostForm::from_post(&existing, Category::find_ids_for_post(&data.pool, post_id).await?)
+    } else {
+        new_post_form()
     };
+    apply_payload(data, payload, &mut form).await?;
+    save_post(data, &user, form, Some(&existing)).await
+}
+
+/// Replaces a post; missing fields fall back to the defaults of a new post.
+#[utoipa::path(
+    put,
+    path = "/api/posts/{id}",
+    tag = "posts",
+    params(("id" = i32, Path, description = "Post id")),
+    request_body = PostPayload,
+    responses(
+        (status = 200, body = Envelope<Blogs>),
+        (status = 400, description = "Malformed JSON", body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 422, description = "`data.errors` lists the problems", body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn replace_post(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    post_id: web::Path<i32>,
+    payload: web::Json<PostPayload>,
+) -> Result<HttpResponse, AppError> {
+    update_post(&data, user, post_id.into_inner(), payload.into_inner(), false).await
+}
+
+/// Changes only the fields present in the body.
+#[utoipa::path(
+    patch,
+    path = "/api/posts/{id}",
+    tag = "posts",
+    params(("id" = i32, Path, description = "Post id")),
+    request_body = PostPayload,
+    responses(
+        (status = 200, body = Envelope<Blogs>),
+        (status = 400, description = "Malformed JSON", body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 422, description = "`data.errors` lists the problems", body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn patch_post(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    post_id: web::Path<i32>,
+    payload: web::Json<PostPayload>,
+) -> Result<HttpResponse, AppError> {
+    update_post(&data, user, post_id.into_inner(), payload.into_inner(), true).await
+}
+
+/// Deletes a post and returns it.
+#[utoipa::path(
+    delete,
+    path = "/api/posts/{id}",
+    tag = "posts",
+    params(("id" = i32, Path, description = "Post id")),
+    responses(
+        (status = 200, body = Envelope<Blogs>),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn delete_post(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    post_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    limit_writes(&data, &user).await?;
+    user.require_scope("posts:write")?;
+
+    editable_post(&data, &user, *post_id).await?;
+
+    let post = Blogs::delete_post(&data.pool, *post_id)
+        .await?
+        .ok_or(AppError::NotFound("Post not found"))?;
+    data.invalidate_posts();
+
+    Ok(HttpResponse::Ok().json(Envelope::found("Post deleted", post)))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ModerationPayload {
+    /// `pending`, `approved`, `rejected` or `spam`.
+    status: String,
+}
 
-    Ok(HttpResponse::Ok().json(json!({
-        "status": true,
-        "code": 1,
-        "message": "Comment added successfully",
-        "data": post
-    })))
+/// Sets the moderation status of a comment.
+#[utoipa::path(
+    patch,
+    path = "/api/comments/{id}",
+    tag = "comments",
+    params(("id" = i32, Path, description = "Comment id")),
+    request_body = ModerationPayload,
+    responses(
+        (status = 200, body = MessageEnvelope),
+        (status = 400, description = "Unknown status or malformed JSON", body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, description = "Missing `comments:moderate` scope or permission", body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn moderate_comment(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    comment_id: web::Path<i32>,
+    payload: web::Json<ModerationPayload>,
+) -> Result<HttpResponse, AppError> {
+    limit_writes(&data, &user).await?;
+    user.require_scope("comments:moderate")?;
+    user.auth.require(Permission::ModerateComments)?;
+
+    if !COMMENT_STATUSES.contains(&payload.status.as_str()) {
+        return Err(AppError::BadRequest("Unknown status".to_string()));
+    }
+
+    match Api::set_status(&data.pool, &[*comment_id], &payload.status).await? {
+        0 => Err(AppError::NotFound("Comment not found")),
+        _ => Ok(HttpResponse::Ok().json(MessageEnvelope::ok("Comment updated"))),
+    }
+}
+
+/// Deletes a comment.
+#[utoipa::path(
+    delete,
+    path = "/api/comments/{id}",
+    tag = "comments",
+    params(("id" = i32, Path, description = "Comment id")),
+    responses(
+        (status = 200, body = MessageEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 403, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 429, description = "Too many writes by this user; see `Retry-After`", body = ErrorEnvelope),
+        (status = 500, body = ErrorEnvelope),
+    ),
+    security(("api_token" = []))
+)]
+pub async fn delete_comment(
+    data: web::Data<AppState>,
+    user: TokenUser,
+    comment_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    limit_writes(&data, &user).await?;
+    user.require_scope("comments:moderate")?;
+    user.auth.require(Permission::ModerateComments)?;
+
+    match Api::delete(&data.pool, &[*comment_id]).await? {
+        0 => Err(AppError::NotFound("Comment not found")),
+        _ => Ok(HttpResponse::Ok().json(MessageEnvelope::ok("Comment deleted"))),
+    }
 }
@@ -1,74 +1,231 @@
-use actix_web::{web, HttpResponse, Responder, Result};
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
 use askama::Template;
-use sqlx::PgPool;
-use moka::future::Cache;
-use std::sync::Arc;
 
+use crate::error::{self, AppError};
 use crate::models::Blogs;
-use crate::templates::blogs::{BlogTemplate, IndexTemplate, PostInfoTemplate};
+use crate::models::blogs::{BlogWithAuthor, Category, HomePageTemp};
+use crate::models::pagination::{PageQuery, Pagination};
+use crate::models::popular::PopularWindow;
+use crate::models::search::{Search, SearchQuery};
+use crate::templates::blogs::{
+    BlogTemplate, CategoriesTemplate, CategoryTemplate, IndexTemplate, NotFoundTemplate, PopularWidgets,
+    PostInfoTemplate, PostNavigation, SearchTemplate,
+};
 use crate::state::AppState;
+use crate::views;
 
-pub async fn index(pool: web::Data<PgPool>) -> Result<HttpResponse> {
-    let posts = match Blogs::find_all_posts_with_authors(&pool).await {
-        Ok(posts) => posts,
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            return Ok(HttpResponse::InternalServerError().finish());
-        }
+#[derive(serde::Deserialize)]
+pub struct PostQuery {
+    // Category slug to keep previous/next links in.
+    #[serde(rename = "in")]
+    pub category: Option<String>,
+}
+
+pub fn not_found_page() -> HttpResponse {
+    let template = NotFoundTemplate {};
+    HttpResponse::NotFound()
+        .content_type("text/html; charset=utf-8")
+        .body(template.render().unwrap_or_else(|_| String::from("404 - Sayfa Bulunamadı")))
+}
+
+pub async fn not_found() -> HttpResponse {
+    not_found_page()
+}
+
+/// Loads one page of the post listing, or `None` when `page` is past the end.
+async fn listing_page(
+    data: &AppState,
+    page: i64,
+    base_path: &str,
+) -> Result<Option<(Vec<HomePageTemp>, Pagination)>, sqlx::Error> {
+    let total = Blogs::count_published_posts(&data.pool).await?;
+    let pagination = Pagination::new(page, data.config.posts_per_page, total, base_path);
+    if pagination.is_out_of_range() {
+        return Ok(None);
+    }
+    let posts = Blogs::find_published_posts(&data.pool, pagination.per_page, pagination.offset()).await?;
+    Ok(Some((posts, pagination)))
+}
+
+/// Loads the popular posts lists. They are a sidebar extra, so a failure
+/// leaves them empty instead of failing the page.
+async fn popular_widgets(data: &AppState) -> PopularWidgets {
+    let trending_days = data.config.trending_days;
+    let load = |window| async move {
+        data.popular_posts(window).await.unwrap_or_else(|e| {
+            error::log_ignored(e);
+            Default::default()
+        })
+    };
+    PopularWidgets {
+        most_read: load(PopularWindow::AllTime).await,
+        trending: load(PopularWindow::Days(trending_days)).await,
+        trending_days,
+    }
+}
+
+fn paginated_html(pagination: &Pagination, html: String) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    response.content_type("text/html; charset=utf-8");
+    if let Some(link) = pagination.link_header() {
+        response.insert_header((header::LINK, link));
+    }
+    response.body(html)
+}
+
+pub async fn index(data: web::Data<AppState>, query: web::Query<PageQuery>) -> Result<HttpResponse, AppError> {
+    let (posts, pagination) = listing_page(&data, query.page(), "/")
+        .await?
+        .ok_or(AppError::NotFound("Page not found"))?;
+
+    let template = IndexTemplate {
+        posts,
+        pagination,
+        popular: popular_widgets(&data).await,
     };
+    let html = template.render().unwrap();
+    Ok(paginated_html(&template.pagination, html))
+}
+
 
-    let template = IndexTemplate { posts };
+pub async fn blogs_page(data: web::Data<AppState>, query: web::Query<PageQuery>) -> Result<HttpResponse, AppError> {
+    let (posts, pagination) = listing_page(&data, query.page(), "/blog")
+        .await?
+        .ok_or(AppError::NotFound("Page not found"))?;
+    let template = BlogTemplate { posts, pagination };
     let html = template.render().unwrap();
+    Ok(paginated_html(&template.pagination, html))
+}
+
+async fn record_view(req: &HttpRequest, data: &AppState, post: &BlogWithAuthor) {
+    let Some(post_id) = post.id else {
+        return;
+    };
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if views::is_bot(user_agent) {
+        return;
+    }
+
+    let ip = views::client_ip(req, data.config.trust_proxy);
+    data.views.record(post_id, &ip, user_agent).await;
+}
+
+/// Builds the previous/next links, scoped to the `?in=` category when it exists.
+async fn post_navigation(
+    data: &AppState,
+    post: &BlogWithAuthor,
+    category_slug: Option<&str>,
+) -> Result<PostNavigation, sqlx::Error> {
+    let category = match category_slug {
+        Some(slug) => Category::find_by_slug(&data.pool, slug).await?,
+        None => None,
+    };
+
+    let (previous, next) = match (post.published_at, post.id) {
+        (Some(published_at), Some(id)) => {
+            Blogs::find_adjacent(&data.pool, published_at, id, category.as_ref().map(|c| c.id)).await?
+        }
+        _ => (None, None),
+    };
+
+    Ok(PostNavigation { previous, next, category })
+}
+
+pub async fn postinfo(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    slug: web::Path<String>,
+    query: web::Query<PostQuery>,
+) -> Result<HttpResponse, AppError> {
+    let slug_str = slug.into_inner();
+
+    let post = match data.post_cache.get(&slug_str).await {
+        Some(cached_post) => cached_post,
+        None => match Blogs::find_post_by_slug(&data.pool, &slug_str, data.config.related_posts_limit).await? {
+            Some(post) => {
+                // Gelen post'u cache'e ekleyelim
+                data.post_cache.insert(slug_str.clone(), post.clone()).await;
+                post
+            }
+            None => return Err(AppError::NotFound("Post not found")),
+        },
+    };
 
+    record_view(&req, &data, &post).await;
+
+    let navigation = post_navigation(&data, &post, query.category.as_deref()).await?;
+
+    let canonical_url = format!("{}/blog/{}", data.config.site_url, post.slug);
+    let template = PostInfoTemplate {
+        post,
+        comment_max_depth: data.config.comment_max_depth,
+        popular: popular_widgets(&data).await,
+        navigation,
+        canonical_url,
+    };
+    let html = template.render().unwrap();
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(html))
 }
 
+pub async fn search_page(
+    data: web::Data<AppState>,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, AppError> {
+    let terms = query.terms().to_string();
 
-pub async fn blogs_page(pool: web::Data<PgPool>) -> Result<HttpResponse> {
-    let posts = match Blogs::find_all_posts_with_authors(&pool).await {
-        Ok(posts) => posts,
-        Err(e) => {
-            eprintln!("Database Error: {}", e);
-            return Ok(HttpResponse::InternalServerError().finish());
-            }
+    let results = if terms.is_empty() {
+        Vec::new()
+    } else {
+        Search::posts(&data.pool, &data.config.search_config, &terms, query.limit()).await?
     };
-    let template = BlogTemplate{posts};
+
+    let template = SearchTemplate { query: terms, results };
+    let html = template.render().unwrap();
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(html))
+}
+
+pub async fn categories_page(data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let categories = Category::find_all_with_counts(&data.pool).await?;
+
+    let template = CategoriesTemplate { categories };
     let html = template.render().unwrap();
     Ok(HttpResponse::Ok()
-    .content_type("text/html; charset=utf-8")
-    .body(html))
+        .content_type("text/html; charset=utf-8")
+        .body(html))
 }
 
-pub async fn postinfo(
+pub async fn category_page(
     data: web::Data<AppState>,
     slug: web::Path<String>,
-) -> impl Responder {
-    let slug_str = slug.into_inner();
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, AppError> {
+    let category = Category::find_by_slug(&data.pool, &slug)
+        .await?
+        .ok_or(AppError::NotFound("Category not found"))?;
 
-    if let Some(cached_post) = data.post_cache.get(&slug_str).await {
-        let template = PostInfoTemplate { post: cached_post };
-        let html = template.render().unwrap();
-        return HttpResponse::Ok()
-            .content_type("text/html; charset=utf-8")
-            .body(html);
-    }
+    let total = Blogs::count_posts_in_category(&data.pool, category.id).await?;
 
-    match Blogs::find_post_by_slug(&data.pool, &slug_str).await {
-        Ok(Some(post)) => {
-            // Gelen post'u cache'e ekleyelim
-            data.post_cache.insert(slug_str.clone(), post.clone()).await;
-            let template = PostInfoTemplate { post };
-            let html = template.render().unwrap();
-            HttpResponse::Ok()
-                .content_type("text/html; charset=utf-8")
-                .body(html)
-        }
-        Ok(None) => HttpResponse::NotFound().body("Post not found"),
-        Err(e) => {
-            eprintln!("Database error: {}", e);
-            HttpResponse::InternalServerError().finish()
-        }
+    let pagination = Pagination::new(
+        query.page(),
+        data.config.posts_per_page,
+        total,
+        format!("/category/{}", category.slug),
+    );
+    if pagination.is_out_of_range() {
+        return Err(AppError::NotFound("Page not found"));
     }
-}
\ No newline at end of file
+
+    let posts = Blogs::find_posts_in_category(&data.pool, category.id, pagination.per_page, pagination.offset()).await?;
+
+    let template = CategoryTemplate { category, posts, pagination };
+    let html = template.render().unwrap();
+    Ok(paginated_html(&template.pagination, html))
+}
@@ -0,0 +1,128 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use askama::Template;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+use crate::models::blogs::Category;
+use crate::models::feeds::FeedEntry;
+use crate::state::AppState;
+use crate::templates::feeds::{AtomTemplate, Feed, JsonFeed, RssTemplate};
+
+const RSS_CONTENT_TYPE: &str = "application/rss+xml; charset=utf-8";
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+const JSON_FEED_CONTENT_TYPE: &str = "application/feed+json; charset=utf-8";
+
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Sends `body` with an ETag (hash of the body) and Last-Modified, or a bare
+/// 304 when the client's `If-None-Match` / `If-Modified-Since` still matches.
+/// `If-None-Match` wins when both are sent, as RFC 9110 requires.
+pub fn conditional_response(
+    req: &HttpRequest,
+    content_type: &str,
+    body: String,
+    last_modified: Option<DateTime<Utc>>,
+) -> HttpResponse {
+    let etag = format!("\"{}\"", &hex::encode(Sha256::digest(body.as_bytes()))[..32]);
+
+    let not_modified = match req.headers().get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) {
+        Some(tags) => tags
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*"),
+        None => match (
+            last_modified,
+            req.headers()
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| DateTime::parse_from_rfc2822(v).ok()),
+        ) {
+            (Some(modified), Some(since)) => modified.timestamp() <= since.timestamp(),
+            _ => false,
+        },
+    };
+
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response.insert_header((header::ETAG, etag));
+    if let Some(modified) = last_modified {
+        response.insert_header((header::LAST_MODIFIED, http_date(modified)));
+    }
+
+    if not_modified {
+        response.finish()
+    } else {
+        response.content_type(content_type).body(body)
+    }
+}
+
+async fn site_feed(data: &AppState, self_path: &str) -> Result<Feed, sqlx::Error> {
+    let config = &data.config;
+    let entries = FeedEntry::find_recent(&data.pool, None, config.feed_limit).await?;
+    Ok(Feed {
+        title: config.site_title.clone(),
+        description: config.site_description.clone(),
+        site_url: config.site_url.clone(),
+        html_url: format!("{}/", config.site_url),
+        self_url: format!("{}{}", config.site_url, self_path),
+        full_content: config.feed_full_content,
+        entries,
+    })
+}
+
+pub async fn rss(req: HttpRequest, data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let feed = site_feed(&data, "/feed.xml").await?;
+
+    let last_modified = feed.updated();
+    let xml = RssTemplate { feed }.render().unwrap();
+    Ok(conditional_response(&req, RSS_CONTENT_TYPE, xml, last_modified))
+}
+
+pub async fn atom(req: HttpRequest, data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let feed = site_feed(&data, "/atom.xml").await?;
+
+    let last_modified = feed.updated();
+    let xml = AtomTemplate { feed }.render().unwrap();
+    Ok(conditional_response(&req, ATOM_CONTENT_TYPE, xml, last_modified))
+}
+
+pub async fn json_feed(req: HttpRequest, data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let feed = site_feed(&data, "/feed.json").await?;
+
+    let last_modified = feed.updated();
+    let json = serde_json::to_string(&JsonFeed::from(feed)).unwrap();
+    Ok(conditional_response(&req, JSON_FEED_CONTENT_TYPE, json, last_modified))
+}
+
+pub async fn category_rss(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    slug: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let category = Category::find_by_slug(&data.pool, &slug)
+        .await?
+        .ok_or(AppError::NotFound("Category not found"))?;
+
+    let config = &data.config;
+    let entries = FeedEntry::find_recent(&data.pool, Some(category.id), config.feed_limit).await?;
+
+    let feed = Feed {
+        title: format!("{} - {}", category.name, config.site_title),
+        description: category.description.clone().unwrap_or_else(|| config.site_description.clone()),
+        site_url: config.site_url.clone(),
+        html_url: format!("{}/category/{}", config.site_url, category.slug),
+        self_url: format!("{}/category/{}/feed.xml", config.site_url, category.slug),
+        full_content: config.feed_full_content,
+        entries,
+    };
+
+    let last_modified = feed.updated();
+    let xml = RssTemplate { feed }.render().unwrap();
+    Ok(conditional_response(&req, RSS_CONTENT_TYPE, xml, last_modified))
+}
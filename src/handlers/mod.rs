@@ -1,2 +1,6 @@
+pub mod admin;
+pub mod api;
 pub mod blogs;
-pub(crate) mod api;
+pub mod feeds;
+pub mod openapi;
+pub mod sitemap;
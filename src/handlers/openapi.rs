@@ -0,0 +1,99 @@
+use actix_web::{web, HttpResponse, Result};
+use askama::Template;
+use std::sync::OnceLock;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::auth::SESSION_COOKIE;
+use crate::handlers::api;
+use crate::state::AppState;
+use crate::templates::api::ApiDocsTemplate;
+
+const SPEC_URL: &str = "/api/openapi.json";
+
+/// The `/api` description. Every path is a handler annotated with
+/// `#[utoipa::path]`, so the document follows the Rust types.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Skyfetch API",
+        description = "Every response uses the `{status, code, message, data}` envelope: \
+                       `code` is 1 for results, 2 for nothing found and 0 for errors. \
+                       Errors also carry a stable `error` code such as `not_found`."
+    ),
+    paths(
+        api::list_posts,
+        api::create_post,
+        api::search_posts,
+        api::popular_posts,
+        api::post_by_slug,
+        api::replace_post,
+        api::patch_post,
+        api::delete_post,
+        api::categories,
+        api::category_posts,
+        api::author,
+        api::comments,
+        api::addcomment,
+        api::moderate_comment,
+        api::delete_comment,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "posts"),
+        (name = "categories"),
+        (name = "authors"),
+        (name = "comments"),
+    )
+)]
+pub struct ApiDoc;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Personal API token (`skf_...`) created at /admin/tokens"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                SESSION_COOKIE,
+                "Admin session from /admin/login",
+            ))),
+        );
+    }
+}
+
+pub async fn openapi_json() -> Result<HttpResponse, actix_web::Error> {
+    // The document only depends on the binary, so it's built once.
+    static SPEC: OnceLock<String> = OnceLock::new();
+    let spec = SPEC.get_or_init(|| {
+        let mut doc = ApiDoc::openapi();
+        // utoipa fills this from Cargo.toml, which names no license.
+        doc.info.license = None;
+        doc.to_json().expect("OpenAPI document should serialize")
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(spec.as_str()))
+}
+
+pub async fn docs(data: web::Data<AppState>) -> Result<HttpResponse, actix_web::Error> {
+    let template = ApiDocsTemplate {
+        site_title: &data.config.site_title,
+        spec_url: SPEC_URL,
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(template.render().unwrap()))
+}
@@ -0,0 +1,72 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use askama::Template;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::handlers::feeds::conditional_response;
+use crate::models::sitemap::{SitemapUrl, Sitemaps};
+use crate::state::AppState;
+use crate::templates::sitemap::{SitemapIndexTemplate, UrlSetTemplate};
+
+// Limit set by the sitemap protocol; past it /sitemap.xml becomes an index.
+const MAX_SITEMAP_URLS: usize = 50_000;
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// Renders `/sitemap.xml` and, for large sites, every `/sitemap-{n}.xml` part.
+async fn build_sitemaps(data: &AppState) -> Result<Sitemaps, sqlx::Error> {
+    let urls = SitemapUrl::find_all(&data.pool).await?;
+    let site_url = data.config.site_url.as_str();
+
+    if urls.len() <= MAX_SITEMAP_URLS {
+        let index = UrlSetTemplate { site_url, urls: &urls }.render().unwrap();
+        return Ok(Sitemaps { index, parts: Vec::new() });
+    }
+
+    let parts: Vec<String> = urls
+        .chunks(MAX_SITEMAP_URLS)
+        .map(|chunk| UrlSetTemplate { site_url, urls: chunk }.render().unwrap())
+        .collect();
+    let index = SitemapIndexTemplate { site_url, parts: (1..=parts.len()).collect() }
+        .render()
+        .unwrap();
+    Ok(Sitemaps { index, parts })
+}
+
+async fn cached_sitemaps(data: &AppState) -> Result<Arc<Sitemaps>, sqlx::Error> {
+    if let Some(sitemaps) = data.sitemap_cache.get(&()).await {
+        return Ok(sitemaps);
+    }
+    let sitemaps = Arc::new(build_sitemaps(data).await?);
+    data.sitemap_cache.insert((), sitemaps.clone()).await;
+    Ok(sitemaps)
+}
+
+pub async fn sitemap(req: HttpRequest, data: web::Data<AppState>) -> Result<HttpResponse, AppError> {
+    let sitemaps = cached_sitemaps(&data).await?;
+    Ok(conditional_response(&req, XML_CONTENT_TYPE, sitemaps.index.clone(), None))
+}
+
+pub async fn sitemap_part(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    part: web::Path<usize>,
+) -> Result<HttpResponse, AppError> {
+    let sitemaps = cached_sitemaps(&data).await?;
+    let xml = sitemaps.part(part.into_inner()).ok_or(AppError::NotFound("Sitemap not found"))?;
+    Ok(conditional_response(&req, XML_CONTENT_TYPE, xml.to_string(), None))
+}
+
+pub async fn robots(data: web::Data<AppState>) -> HttpResponse {
+    let mut body = String::from("User-agent: *\n");
+    for path in &data.config.robots_disallow {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    if data.config.robots_disallow.is_empty() {
+        body.push_str("Disallow:\n");
+    }
+    body.push_str(&format!("\nSitemap: {}/sitemap.xml\n", data.config.site_url));
+
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(body)
+}
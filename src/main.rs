@@ -1,29 +1,94 @@
+mod auth;
+mod config;
+mod content;
+mod error;
 mod models;
+mod permissions;
+mod rate_limit;
 mod routes;
 mod handlers;
 mod templates;
 mod filters;
+mod scheduler;
 mod state;
+mod views;
 
 use actix_web::{App, HttpServer, web, middleware};
+use actix_web::middleware::ErrorHandlers;
 use actix_files as fs;
 use actix_cors::Cors;
 use dotenv::dotenv;
 use std::time::Duration;
 use state::AppState;
+use config::Config;
 use moka::future::Cache;
 use std::sync::Arc;
-use crate::routes::configure_routes;
+use models::db::DatabaseError;
+use models::migrate::MigrationError;
+
+// `blogprojesi migrate <up|down [steps]|status>`
+async fn run_migrate_command(args: &[String]) -> std::io::Result<()> {
+    let pool = models::db::connect()
+        .await
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+    let result = match args.first().map(String::as_str) {
+        Some("up") | None => models::migrate::run_pending(&pool).await.map(|applied| {
+            println!("Applied {} migration(s)", applied.len());
+        }),
+        Some("down") => {
+            let steps = args.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
+            models::migrate::rollback(&pool, steps).await.map(|reverted| {
+                println!("Reverted {} migration(s)", reverted.len());
+            })
+        }
+        Some("status") => models::migrate::print_status(&pool).await,
+        Some(other) => {
+            eprintln!("Unknown migrate command: {}", other);
+            eprintln!("Usage: blogprojesi migrate <up|down [steps]|status>");
+            return Err(std::io::Error::other("invalid migrate command"));
+        }
+    };
+
+    pool.close().await;
+    result.map_err(|e| std::io::Error::other(e.to_string()))
+}
+
+const HIGHLIGHT_CSS_PATH: &str = "static/css/highlight.css";
+
+// `blogprojesi highlight-css [theme]` regenerates the code highlighting stylesheet.
+fn run_highlight_css_command(args: &[String]) -> std::io::Result<()> {
+    let theme = args.first().map_or(content::highlight::DEFAULT_THEME, String::as_str);
+    let css = content::highlight::theme_css(theme).map_err(std::io::Error::other)?;
+    std::fs::write(HIGHLIGHT_CSS_PATH, css)?;
+    println!("Wrote {} ({})", HIGHLIGHT_CSS_PATH, theme);
+    Ok(())
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("migrate") => return run_migrate_command(&args[1..]).await,
+        Some("highlight-css") => return run_highlight_css_command(&args[1..]),
+        _ => {}
+    }
+
     let pool = loop {
         match models::db::init_db().await {
             Ok(pool) => {
                 println!("Database connection successful!");
                 break pool;
             }
+            Err(DatabaseError::Migration(e)) if !matches!(e, MigrationError::Database(_)) => {
+                // Retrying won't fix a schema mismatch, so stop here. Database
+                // errors while migrating (e.g. a dropped connection) fall
+                // through to the retry below.
+                eprintln!("{}", e);
+                return Err(std::io::Error::other(e.to_string()));
+            }
             Err(e) => {
                 println!("Database connection failed: {:?}. Retrying in 5 seconds...", e);
                 tokio::time::sleep(Duration::from_secs(5)).await;
@@ -32,6 +97,22 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    let config = Config::from_env();
+
+    match content::render_missing(&pool, &config).await {
+        Ok(0) => {}
+        Ok(rendered) => println!("Rendered content of {} post(s)", rendered),
+        Err(e) => {
+            eprintln!("Content rendering error: {}", e);
+            return Err(std::io::Error::other(e.to_string()));
+        }
+    }
+
+    if let Err(e) = models::search::Search::sync_config(&pool, &config.search_config).await {
+        eprintln!("Search configuration error: {}", e);
+        return Err(std::io::Error::other(e.to_string()));
+    }
+
     let post_cache = Arc::new(
         Cache::builder()
             .max_capacity(1000)
@@ -39,11 +120,43 @@ async fn main() -> std::io::Result<()> {
             .build(),
     );
 
+    // Rebuilt on demand after invalidate_posts; the TTL only catches changes
+    // made directly in the database.
+    let sitemap_cache = Arc::new(
+        Cache::builder()
+            .max_capacity(1)
+            .time_to_live(Duration::from_secs(60 * 60))
+            .build(),
+    );
+
+    let popular_cache = Arc::new(
+        Cache::builder()
+            .max_capacity(100)
+            .time_to_live(config.popular_cache_ttl)
+            .build(),
+    );
+
     let app_state = AppState {
         pool: pool.clone(),
         post_cache,
+        views: Arc::new(views::ViewCounter::new(config.view_window)),
+        comment_limiter: Arc::new(rate_limit::RateLimiter::new(
+            config.comment_rate_limit,
+            config.comment_rate_window,
+        )),
+        write_limiter: Arc::new(rate_limit::RateLimiter::new(
+            config.api_write_rate_limit,
+            Duration::from_secs(60),
+        )),
+        config: Arc::new(config),
+        sitemap_cache,
+        popular_cache,
     };
 
+    actix_web::rt::spawn(scheduler::run(app_state.clone()));
+    actix_web::rt::spawn(views::run_flusher(app_state.clone()));
+    let shutdown_state = app_state.clone();
+
     println!("Server running at http://localhost:8080");
 
     HttpServer::new(move || {
@@ -53,10 +166,13 @@ async fn main() -> std::io::Result<()> {
             .allow_any_header();
 
         App::new()
+            // Innermost, so the error pages still go through compression and CORS.
+            .wrap(ErrorHandlers::new().default_handler(error::html_error_page))
             .wrap(cors)
             .wrap(middleware::Compress::default())
             .wrap(middleware::Logger::default())
             .app_data(web::Data::new(app_state.clone()))
+            .app_data(web::Data::new(pool.clone()))
             .service(
                 fs::Files::new("/static", "static")
                     .show_files_listing()
@@ -71,5 +187,9 @@ async fn main() -> std::io::Result<()> {
         .keep_alive(Duration::from_secs(30))
         .bind("127.0.0.1:8080")?
         .run()
-        .await
+        .await?;
+
+    // Don't lose the views counted since the last flush.
+    views::flush(&shutdown_state).await;
+    Ok(())
 }
\ No newline at end of file
@@ -1,58 +1,252 @@
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use sqlx::postgres::PgPool;
-use html_escape::encode_text;
+use std::collections::HashMap;
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+pub const COMMENT_STATUSES: &[&str] = &["pending", "approved", "rejected", "spam"];
+/// Longest comment accepted, in characters.
+pub const COMMENT_MAX_LENGTH: u64 = 5000;
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
 pub struct Api {
     pub id: Option<i32>,
     pub post_id: Option<i32>,
     pub user_id: Option<i32>,
+    pub parent_id: Option<i32>,
     pub content: Option<String>,
     pub author_name: Option<String>,
-    pub author_email: Option<String>,
+    // No author_email: it's only shown to moderators (ModerationComment),
+    // never in the public API.
     pub status: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// An approved comment with its approved replies, as returned by the public API.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: Api,
+    pub depth: usize,
+    #[schema(no_recursion)]
+    pub replies: Vec<CommentNode>,
+}
+
+/// Where a reply would be attached: the parent's post and nesting depth.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ReplyTarget {
+    pub post_id: Option<i32>,
+    pub status: String,
+    pub depth: i32,
+}
+
 impl Api {
-    pub async fn find_comments(pool: &PgPool, post_id: &i32) -> Result<Vec<Api>, sqlx::Error> {
+    /// Approved comments of a post as a tree. Top-level comments are newest
+    /// first, replies oldest first. Replies below `max_depth` levels, or under
+    /// a comment that isn't approved, are left out.
+    pub async fn find_comments(pool: &PgPool, post_id: &i32, max_depth: usize) -> Result<Vec<CommentNode>, sqlx::Error> {
         let comments = sqlx::query_as::<_, Api>(
             r#"
-            SELECT * FROM comments WHERE post_id = $1 ORDER BY created_at DESC
+            SELECT * FROM comments
+            WHERE post_id = $1 AND status = 'approved'
+            ORDER BY created_at ASC
             "#
         )
             .bind(post_id)
             .fetch_all(pool)
             .await?;
 
-        Ok(comments)
+        let mut roots = build_tree(comments, max_depth);
+        roots.reverse();
+        Ok(roots)
+    }
+
+    /// Looks up the comment a reply points at, with its depth in the thread
+    /// (0 for a top-level comment).
+    pub async fn find_reply_target(pool: &PgPool, parent_id: i32) -> Result<Option<ReplyTarget>, sqlx::Error> {
+        sqlx::query_as::<_, ReplyTarget>(
+            r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT id, parent_id, 0 AS depth FROM comments WHERE id = $1
+                UNION ALL
+                SELECT comments.id, comments.parent_id, ancestors.depth + 1
+                FROM comments
+                INNER JOIN ancestors ON comments.id = ancestors.parent_id
+            )
+            SELECT
+                comments.post_id,
+                comments.status,
+                (SELECT MAX(depth) FROM ancestors) AS depth
+            FROM comments
+            WHERE comments.id = $1
+            "#
+        )
+            .bind(parent_id)
+            .fetch_optional(pool)
+            .await
     }
 
     pub async fn add_comment(
         pool: &PgPool,
         post_id: &i32,
+        parent_id: Option<i32>,
         username: &String,
+        author_email: Option<&str>,
         content: &String
     ) -> Result<Api, sqlx::Error> {
-        let clean_content = encode_text(content);
-        let clean_username = encode_text(username);
-
         let comment = sqlx::query_as::<_, Api>(
             r#"
-        INSERT INTO comments (post_id, user_id, author_name, content, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        INSERT INTO comments (post_id, user_id, parent_id, author_name, author_email, content, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
         RETURNING *
         "#
         )
             .bind(post_id)
             .bind(1)
-            .bind(clean_username)
-            .bind(clean_content)
+            .bind(parent_id)
+            .bind(username)
+            .bind(author_email)
+            .bind(content)
             .fetch_one(pool)
             .await?;
 
         Ok(comment)
     }
-}
\ No newline at end of file
+
+    /// Whether a post takes new comments: `None` if it doesn't exist or isn't
+    /// public, `Some(false)` once it's older than `close_after_days` (0 keeps
+    /// comments open).
+    pub async fn accepts_comments(pool: &PgPool, post_id: i32, close_after_days: i32) -> Result<Option<bool>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT $2 = 0 OR published_at > NOW() - make_interval(days => $2)
+            FROM posts
+            WHERE id = $1 AND status = 'published' AND published_at <= NOW()
+            "#,
+        )
+            .bind(post_id)
+            .bind(close_after_days)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Comments for the moderation queue, newest first, with the post they belong to.
+    pub async fn find_for_moderation(pool: &PgPool, status: &str) -> Result<Vec<ModerationComment>, sqlx::Error> {
+        sqlx::query_as::<_, ModerationComment>(
+            r#"
+            SELECT
+                comments.id,
+                comments.post_id,
+                comments.content,
+                comments.author_name,
+                comments.author_email,
+                comments.status,
+                comments.created_at,
+                posts.title as post_title,
+                posts.slug as post_slug
+            FROM comments
+            LEFT JOIN posts ON comments.post_id = posts.id
+            WHERE comments.status = $1
+            ORDER BY comments.created_at DESC
+            "#
+        )
+            .bind(status)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn set_status(pool: &PgPool, ids: &[i32], status: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE comments SET status = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ANY($1)
+            "#
+        )
+            .bind(ids)
+            .bind(status)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes the given comments together with every reply below them.
+    pub async fn delete(pool: &PgPool, ids: &[i32]) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            WITH RECURSIVE doomed AS (
+                SELECT id FROM comments WHERE id = ANY($1)
+                UNION
+                SELECT comments.id FROM comments
+                INNER JOIN doomed ON comments.parent_id = doomed.id
+            )
+            DELETE FROM comments WHERE id IN (SELECT id FROM doomed)
+            "#
+        )
+            .bind(ids)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Number of comments in every moderation status, including empty ones.
+    pub async fn count_by_status(pool: &PgPool) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT statuses.status, COUNT(comments.id)
+            FROM UNNEST($1::TEXT[]) WITH ORDINALITY AS statuses(status, position)
+            LEFT JOIN comments ON comments.status = statuses.status
+            GROUP BY statuses.status, statuses.position
+            ORDER BY statuses.position
+            "#
+        )
+            .bind(COMMENT_STATUSES)
+            .fetch_all(pool)
+            .await
+    }
+}
+
+// `comments` must be sorted oldest first; siblings keep that order.
+fn build_tree(comments: Vec<Api>, max_depth: usize) -> Vec<CommentNode> {
+    let mut children: HashMap<Option<i32>, Vec<Api>> = HashMap::new();
+    for comment in comments {
+        children.entry(comment.parent_id).or_default().push(comment);
+    }
+    attach_replies(&mut children, None, 0, max_depth)
+}
+
+fn attach_replies(
+    children: &mut HashMap<Option<i32>, Vec<Api>>,
+    parent_id: Option<i32>,
+    depth: usize,
+    max_depth: usize,
+) -> Vec<CommentNode> {
+    if depth >= max_depth {
+        return Vec::new();
+    }
+
+    children
+        .remove(&parent_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|comment| {
+            let replies = attach_replies(children, comment.id, depth + 1, max_depth);
+            CommentNode { comment, depth, replies }
+        })
+        .collect()
+}
+
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct ModerationComment {
+    pub id: i32,
+    pub post_id: Option<i32>,
+    pub content: String,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub post_title: Option<String>,
+    pub post_slug: Option<String>,
+}
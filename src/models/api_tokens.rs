@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::postgres::PgPool;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+use crate::models::users::User;
+
+pub const TOKEN_PREFIX: &str = "skf_";
+
+/// What a token may be used for. The user's role still applies on top.
+pub const API_SCOPES: &[&str] = &["posts:read", "posts:write", "comments:moderate"];
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone)]
+pub struct ApiToken {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A freshly generated token. `value()` is shown to the user once; only the
+/// prefix and a hash of the secret are stored.
+pub struct NewToken {
+    pub prefix: String,
+    pub secret: String,
+}
+
+impl NewToken {
+    pub fn generate() -> Self {
+        let mut prefix = [0u8; 8];
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut prefix);
+        OsRng.fill_bytes(&mut secret);
+        NewToken {
+            prefix: hex::encode(prefix),
+            secret: hex::encode(secret),
+        }
+    }
+
+    pub fn value(&self) -> String {
+        format!("{}{}_{}", TOKEN_PREFIX, self.prefix, self.secret)
+    }
+}
+
+/// Splits `skf_<prefix>_<secret>` into prefix and secret.
+pub fn parse_token(token: &str) -> Option<(&str, &str)> {
+    let (prefix, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+    if prefix.len() == 16 && !secret.is_empty() {
+        Some((prefix, secret))
+    } else {
+        None
+    }
+}
+
+impl ApiToken {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|date| date <= Utc::now())
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        user_id: i32,
+        name: &str,
+        prefix: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, sqlx::Error> {
+        sqlx::query_as::<_, ApiToken>(
+            r#"
+            INSERT INTO api_tokens (user_id, name, prefix, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+            .bind(user_id)
+            .bind(name)
+            .bind(prefix)
+            .bind(token_hash)
+            .bind(scopes)
+            .bind(expires_at)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_for_user(pool: &PgPool, user_id: i32) -> Result<Vec<ApiToken>, sqlx::Error> {
+        sqlx::query_as::<_, ApiToken>(
+            "SELECT * FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC"
+        )
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// The token with this prefix and its owner. The secret still has to be
+    /// verified against `token_hash`.
+    pub async fn find_by_prefix(pool: &PgPool, prefix: &str) -> Result<Option<(ApiToken, User)>, sqlx::Error> {
+        let Some(token) = sqlx::query_as::<_, ApiToken>("SELECT * FROM api_tokens WHERE prefix = $1")
+            .bind(prefix)
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(token.user_id)
+            .fetch_optional(pool)
+            .await?;
+        Ok(user.map(|user| (token, user)))
+    }
+
+    pub async fn touch(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes one of the user's tokens; false if it wasn't theirs or didn't exist.
+    pub async fn delete(pool: &PgPool, id: i32, user_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM api_tokens WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use sqlx::postgres::PgPool;
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone)]
+use crate::config::Config;
+use crate::content::{self, CONTENT_FORMATS};
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone, ToSchema)]
 pub struct Category {
     pub id: i32,
     pub name: String,
@@ -10,12 +14,42 @@ pub struct Category {
     pub description: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone, ToSchema)]
+pub struct CategoryWithCount {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub post_count: i64,
+}
+
+/// A post suggested under another one, by shared categories or a similar title.
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone, ToSchema)]
+pub struct RelatedPost {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    pub featured_image: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// The post linked by a previous/next link.
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone, ToSchema)]
+pub struct AdjacentPost {
+    pub title: String,
+    pub slug: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
 pub struct Blogs {
     pub id: Option<i32>,
     pub title: String,
     pub slug: String,
+    // What the author wrote, in `content_format`; `content_html` is the rendered copy.
     pub content: Option<String>,
+    pub content_format: String,
+    pub content_html: Option<String>,
     pub excerpt: Option<String>,
     pub featured_image: Option<String>,
     pub author_id: i32,
@@ -26,12 +60,13 @@ pub struct Blogs {
     pub view_count: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow,Clone)]
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow,Clone, ToSchema)]
 pub struct BlogWithAuthor {
     pub id: Option<i32>,
     pub title: String,
     pub slug: String,
     pub content: Option<String>,
+    pub content_html: Option<String>,
     pub excerpt: Option<String>,
     pub featured_image: Option<String>,
     pub author_id: i32,
@@ -46,18 +81,20 @@ pub struct BlogWithAuthor {
     pub author_description: Option<String>,
     #[sqlx(skip)]
     pub categories: Vec<Category>,
+    #[sqlx(skip)]
+    pub related: Vec<RelatedPost>,
 }
 
 impl BlogWithAuthor {
 
 }
 
-#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+// Listing row: everything but the post body, which listings never render.
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
 pub struct HomePageTemp {
     pub id: Option<i32>,
     pub title: String,
     pub slug: String,
-    pub content: Option<String>,
     pub excerpt: Option<String>,
     pub featured_image: Option<String>,
     pub author_id: i32,
@@ -72,44 +109,440 @@ pub struct HomePageTemp {
 
 }
 
-#[derive(Debug, Serialize)]
-pub struct BlogsPageTemp {
-    pub id: i32,
+pub const POST_STATUSES: &[&str] = &["draft", "pending", "scheduled", "published"];
+
+/// Filters for the `/api/posts` listing. Without a `status` only posts that
+/// are publicly visible are matched.
+#[derive(Debug, Default)]
+pub struct PostFilter {
+    pub category_id: Option<i32>,
+    pub author_id: Option<i32>,
+    pub status: Option<String>,
+    // Published in [published_from, published_until).
+    pub published_from: Option<DateTime<Utc>>,
+    pub published_until: Option<DateTime<Utc>>,
+}
+
+/// Validated values for creating or updating a post.
+pub struct PostInput {
     pub title: String,
     pub slug: String,
+    pub content: String,
+    pub content_format: String,
+    // `content` rendered for display; computed by `PostForm::validate`.
+    pub content_html: String,
+    pub excerpt: Option<String>,
+    pub featured_image: Option<String>,
+    pub status: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub category_ids: Vec<i32>,
+}
+
+/// Raw post editor fields, kept as strings so the form can be re-rendered
+/// as typed when validation fails.
+#[derive(Default)]
+pub struct PostForm {
+    pub title: String,
+    pub slug: String,
+    pub content: String,
+    pub content_format: String,
+    pub excerpt: String,
+    pub featured_image: String,
+    pub status: String,
+    pub published_at: String,
+    pub category_ids: Vec<i32>,
+}
+
+/// JSON body of the post write API. Missing fields keep the value of the form
+/// it's applied to: the defaults for POST and PUT, the stored post for PATCH.
+#[derive(Deserialize, Debug, Default, ToSchema)]
+pub struct PostPayload {
+    pub title: Option<String>,
+    pub slug: Option<String>,
     pub content: Option<String>,
+    pub content_format: Option<String>,
     pub excerpt: Option<String>,
     pub featured_image: Option<String>,
+    pub status: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
-    pub view_count: Option<i32>,
-    pub author_id: i32,
-    pub author_name: String,
-    pub author_image: Option<String>,
-    pub category: String,
+    /// Category slugs.
+    pub categories: Option<Vec<String>>,
+}
+
+impl PostPayload {
+    /// Copies the given fields into `form`, resolving category slugs against
+    /// `categories`. Unknown slugs are returned as errors.
+    pub fn apply_to(self, form: &mut PostForm, categories: &[Category]) -> Result<(), Vec<String>> {
+        let fields = [
+            (self.title, &mut form.title),
+            (self.slug, &mut form.slug),
+            (self.content, &mut form.content),
+            (self.content_format, &mut form.content_format),
+            (self.excerpt, &mut form.excerpt),
+            (self.featured_image, &mut form.featured_image),
+            (self.status, &mut form.status),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+
+        if let Some(date) = self.published_at {
+            form.published_at = date.format(FORM_DATETIME_FORMAT).to_string();
+        }
+
+        if let Some(slugs) = self.categories {
+            let mut errors = Vec::new();
+            form.category_ids.clear();
+            for slug in slugs {
+                match categories.iter().find(|category| category.slug == slug) {
+                    Some(category) => form.category_ids.push(category.id),
+                    None => errors.push(format!("\"{}\" adında bir kategori yok", slug)),
+                }
+            }
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Value format of <input type="datetime-local">, interpreted as UTC.
+pub const FORM_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() { None } else { Some(value.to_string()) }
+}
+
+/// Builds a URL slug, transliterating Turkish letters.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        let c = match c {
+            'ç' | 'Ç' => 'c',
+            'ğ' | 'Ğ' => 'g',
+            'ı' | 'I' | 'İ' => 'i',
+            'ö' | 'Ö' => 'o',
+            'ş' | 'Ş' => 's',
+            'ü' | 'Ü' => 'u',
+            c => c.to_ascii_lowercase(),
+        };
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+impl PostForm {
+    pub fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        let mut form = PostForm::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "title" => form.title = value,
+                "slug" => form.slug = value,
+                "content" => form.content = value,
+                "content_format" => form.content_format = value,
+                "excerpt" => form.excerpt = value,
+                "featured_image" => form.featured_image = value,
+                "status" => form.status = value,
+                "published_at" => form.published_at = value,
+                "category_ids" => {
+                    if let Ok(id) = value.parse() {
+                        form.category_ids.push(id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        form
+    }
+
+    pub fn from_post(post: &Blogs, category_ids: Vec<i32>) -> Self {
+        PostForm {
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            content: post.content.clone().unwrap_or_default(),
+            content_format: post.content_format.clone(),
+            excerpt: post.excerpt.clone().unwrap_or_default(),
+            featured_image: post.featured_image.clone().unwrap_or_default(),
+            status: post.status.clone().unwrap_or_else(|| "draft".to_string()),
+            published_at: post
+                .published_at
+                .map(|d| d.format(FORM_DATETIME_FORMAT).to_string())
+                .unwrap_or_default(),
+            category_ids,
+        }
+    }
+
+    /// Checks the fields and returns either the typed input or a list of
+    /// messages to show above the form. The body is rendered and sanitized here.
+    pub fn validate(&self, config: &Config) -> Result<PostInput, Vec<String>> {
+        let mut errors = Vec::new();
+
+        let title = self.title.trim().to_string();
+        if title.is_empty() {
+            errors.push("Başlık boş olamaz".to_string());
+        } else if title.chars().count() > 255 {
+            errors.push("Başlık en fazla 255 karakter olabilir".to_string());
+        }
+
+        let slug = match non_empty(&self.slug) {
+            Some(slug) => slugify(&slug),
+            None => slugify(&title),
+        };
+        if slug.is_empty() {
+            errors.push("Geçerli bir kısa ad (slug) girin".to_string());
+        } else if slug.len() > 255 {
+            errors.push("Kısa ad en fazla 255 karakter olabilir".to_string());
+        }
+
+        if self.content.trim().is_empty() {
+            errors.push("İçerik boş olamaz".to_string());
+        }
+
+        let content_format = self.content_format.trim().to_string();
+        if !CONTENT_FORMATS.contains(&content_format.as_str()) {
+            errors.push("Geçersiz içerik biçimi".to_string());
+        }
+
+        let featured_image = non_empty(&self.featured_image);
+        if featured_image.as_ref().is_some_and(|url| url.len() > 255) {
+            errors.push("Görsel adresi en fazla 255 karakter olabilir".to_string());
+        }
+
+        let status = self.status.trim().to_string();
+        if !POST_STATUSES.contains(&status.as_str()) {
+            errors.push("Geçersiz durum".to_string());
+        }
+
+        let mut published_at = None;
+        if let Some(value) = non_empty(&self.published_at) {
+            match chrono::NaiveDateTime::parse_from_str(&value, FORM_DATETIME_FORMAT) {
+                Ok(date) => published_at = Some(date.and_utc()),
+                Err(_) => errors.push("Geçersiz yayın tarihi".to_string()),
+            }
+        }
+        // A publish date in the future means scheduling, and a scheduled post
+        // whose date has passed is simply published.
+        let status = match (status.as_str(), published_at) {
+            ("published", None) => {
+                published_at = Some(Utc::now());
+                status
+            }
+            ("published", Some(date)) if date > Utc::now() => "scheduled".to_string(),
+            ("scheduled", None) => {
+                errors.push("Zamanlanmış yazılar için yayın tarihi gerekli".to_string());
+                status
+            }
+            ("scheduled", Some(date)) if date <= Utc::now() => "published".to_string(),
+            _ => status,
+        };
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(PostInput {
+            title,
+            slug,
+            content: self.content.clone(),
+            content_html: content::render(config, &content_format, &self.content),
+            content_format,
+            excerpt: non_empty(&self.excerpt),
+            featured_image,
+            status,
+            published_at,
+            category_ids: self.category_ids.clone(),
+        })
+    }
 }
 
 impl Blogs {
 
 
 
-    pub async fn find_all_posts_with_authors(pool: &PgPool) -> Result<Vec<HomePageTemp>, sqlx::Error> {
+    pub async fn find_published_posts(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<HomePageTemp>, sqlx::Error> {
         sqlx::query_as::<_, HomePageTemp>(
             r#"
             SELECT
-                posts.*,
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.excerpt,
+                posts.featured_image,
+                posts.author_id,
+                posts.status,
+                posts.published_at,
+                posts.created_at,
+                posts.updated_at,
+                posts.view_count,
                 users.username as author_name,
                 users.profile_image as author_image
 
             FROM posts
             LEFT JOIN users ON posts.author_id = users.id
-            ORDER BY posts.updated_at DESC NULLS LAST
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            ORDER BY posts.published_at DESC, posts.id DESC
+            LIMIT $1 OFFSET $2
+            "#
+        )
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_filtered(
+        pool: &PgPool,
+        filter: &PostFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<HomePageTemp>, sqlx::Error> {
+        sqlx::query_as::<_, HomePageTemp>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.excerpt,
+                posts.featured_image,
+                posts.author_id,
+                posts.status,
+                posts.published_at,
+                posts.created_at,
+                posts.updated_at,
+                posts.view_count,
+                users.username as author_name,
+                users.profile_image as author_image
+
+            FROM posts
+            LEFT JOIN users ON posts.author_id = users.id
+            WHERE (
+                ($3::TEXT IS NULL AND posts.status = 'published' AND posts.published_at <= NOW())
+                OR posts.status = $3
+            )
+            AND ($1::INTEGER IS NULL OR EXISTS(
+                SELECT 1 FROM categories_relationships
+                WHERE categories_relationships.post_id = posts.id
+                AND categories_relationships.term_id = $1
+            ))
+            AND ($2::INTEGER IS NULL OR posts.author_id = $2)
+            AND ($4::TIMESTAMPTZ IS NULL OR posts.published_at >= $4)
+            AND ($5::TIMESTAMPTZ IS NULL OR posts.published_at < $5)
+            ORDER BY posts.published_at DESC NULLS LAST, posts.id DESC
+            LIMIT $6 OFFSET $7
             "#
         )
+            .bind(filter.category_id)
+            .bind(filter.author_id)
+            .bind(filter.status.as_deref())
+            .bind(filter.published_from)
+            .bind(filter.published_until)
+            .bind(limit)
+            .bind(offset)
             .fetch_all(pool)
             .await
     }
 
-    pub async fn find_post_by_slug(pool: &PgPool, slug: &str) -> Result<Option<BlogWithAuthor>, sqlx::Error> {
+    pub async fn count_filtered(pool: &PgPool, filter: &PostFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM posts
+            WHERE (
+                ($3::TEXT IS NULL AND posts.status = 'published' AND posts.published_at <= NOW())
+                OR posts.status = $3
+            )
+            AND ($1::INTEGER IS NULL OR EXISTS(
+                SELECT 1 FROM categories_relationships
+                WHERE categories_relationships.post_id = posts.id
+                AND categories_relationships.term_id = $1
+            ))
+            AND ($2::INTEGER IS NULL OR posts.author_id = $2)
+            AND ($4::TIMESTAMPTZ IS NULL OR posts.published_at >= $4)
+            AND ($5::TIMESTAMPTZ IS NULL OR posts.published_at < $5)
+            "#
+        )
+            .bind(filter.category_id)
+            .bind(filter.author_id)
+            .bind(filter.status.as_deref())
+            .bind(filter.published_from)
+            .bind(filter.published_until)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn count_published_posts(pool: &PgPool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM posts WHERE status = 'published' AND published_at <= NOW()")
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn find_posts_in_category(
+        pool: &PgPool,
+        category_id: i32,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<HomePageTemp>, sqlx::Error> {
+        sqlx::query_as::<_, HomePageTemp>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.excerpt,
+                posts.featured_image,
+                posts.author_id,
+                posts.status,
+                posts.published_at,
+                posts.created_at,
+                posts.updated_at,
+                posts.view_count,
+                users.username as author_name,
+                users.profile_image as author_image
+
+            FROM posts
+            INNER JOIN categories_relationships ON categories_relationships.post_id = posts.id
+            LEFT JOIN users ON posts.author_id = users.id
+            WHERE categories_relationships.term_id = $1
+            AND posts.status = 'published' AND posts.published_at <= NOW()
+            ORDER BY posts.published_at DESC, posts.id DESC
+            LIMIT $2 OFFSET $3
+            "#
+        )
+            .bind(category_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn count_posts_in_category(pool: &PgPool, category_id: i32) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM posts
+            INNER JOIN categories_relationships ON categories_relationships.post_id = posts.id
+            WHERE categories_relationships.term_id = $1
+            AND posts.status = 'published' AND posts.published_at <= NOW()
+            "#
+        )
+            .bind(category_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// A published post with its categories and up to `related_limit` related posts.
+    pub async fn find_post_by_slug(
+        pool: &PgPool,
+        slug: &str,
+        related_limit: i64,
+    ) -> Result<Option<BlogWithAuthor>, sqlx::Error> {
         let mut post = sqlx::query_as::<_, BlogWithAuthor>(
             r#"
         SELECT
@@ -121,6 +554,7 @@ impl Blogs {
         FROM posts
         LEFT JOIN users ON posts.author_id = users.id
         WHERE posts.slug = $1
+        AND posts.status = 'published' AND posts.published_at <= NOW()
         LIMIT 1
         "#
         )
@@ -146,12 +580,417 @@ impl Blogs {
                 .await?;
 
             post.categories = categories;
+            if let Some(id) = post.id {
+                post.related = Self::find_related(pool, id, related_limit).await?;
+            }
+        }
+
+        Ok(post)
+    }
+
+    /// Published posts sharing the most categories with `post_id`. Ties, and
+    /// posts without shared categories, are ranked by how many title words
+    /// they share with this post, taken from the stored search vectors.
+    pub async fn find_related(pool: &PgPool, post_id: i32, limit: i64) -> Result<Vec<RelatedPost>, sqlx::Error> {
+        sqlx::query_as::<_, RelatedPost>(
+            r#"
+            WITH current AS (
+                SELECT
+                    posts.id,
+                    -- The title is the weight A part of search_vector
+                    tsvector_to_array(ts_filter(posts.search_vector, '{a}')) as title_lexemes
+                FROM posts
+                WHERE posts.id = $1
+            ),
+            candidates AS (
+                SELECT
+                    posts.*,
+                    (
+                        SELECT COUNT(*)
+                        FROM categories_relationships mine
+                        INNER JOIN categories_relationships theirs ON theirs.term_id = mine.term_id
+                        WHERE mine.post_id = current.id AND theirs.post_id = posts.id
+                    ) as shared_categories,
+                    -- Title lexemes left out by ts_delete are the shared ones
+                    COALESCE(
+                        length(ts_filter(posts.search_vector, '{a}'))
+                        - length(ts_delete(ts_filter(posts.search_vector, '{a}'), current.title_lexemes)),
+                        0
+                    ) as shared_title_words
+                FROM posts
+                CROSS JOIN current
+                WHERE posts.status = 'published' AND posts.published_at <= NOW()
+                AND posts.id <> current.id
+            )
+            SELECT id, title, slug, excerpt, featured_image, published_at
+            FROM candidates
+            WHERE shared_categories > 0 OR shared_title_words > 0
+            ORDER BY shared_categories DESC, shared_title_words DESC, published_at DESC
+            LIMIT $2
+            "#
+        )
+            .bind(post_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// The published posts just before (older) and after (newer) the one at
+    /// `published_at`/`id`, optionally within one category. Each side is a
+    /// single index range scan on `posts_published_idx`.
+    pub async fn find_adjacent(
+        pool: &PgPool,
+        published_at: DateTime<Utc>,
+        id: i32,
+        category_id: Option<i32>,
+    ) -> Result<(Option<AdjacentPost>, Option<AdjacentPost>), sqlx::Error> {
+        let previous = sqlx::query_as::<_, AdjacentPost>(
+            r#"
+            SELECT posts.title, posts.slug
+            FROM posts
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            AND (posts.published_at, posts.id) < ($1, $2)
+            AND ($3::INTEGER IS NULL OR EXISTS(
+                SELECT 1 FROM categories_relationships
+                WHERE categories_relationships.post_id = posts.id
+                AND categories_relationships.term_id = $3
+            ))
+            ORDER BY posts.published_at DESC, posts.id DESC
+            LIMIT 1
+            "#
+        )
+            .bind(published_at)
+            .bind(id)
+            .bind(category_id)
+            .fetch_optional(pool)
+            .await?;
+
+        let next = sqlx::query_as::<_, AdjacentPost>(
+            r#"
+            SELECT posts.title, posts.slug
+            FROM posts
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            AND (posts.published_at, posts.id) > ($1, $2)
+            AND ($3::INTEGER IS NULL OR EXISTS(
+                SELECT 1 FROM categories_relationships
+                WHERE categories_relationships.post_id = posts.id
+                AND categories_relationships.term_id = $3
+            ))
+            ORDER BY posts.published_at ASC, posts.id ASC
+            LIMIT 1
+            "#
+        )
+            .bind(published_at)
+            .bind(id)
+            .bind(category_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok((previous, next))
+    }
+
+    /// Posts for the admin list, optionally limited to one author.
+    pub async fn find_admin_posts(pool: &PgPool, author_id: Option<i32>) -> Result<Vec<HomePageTemp>, sqlx::Error> {
+        sqlx::query_as::<_, HomePageTemp>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.excerpt,
+                posts.featured_image,
+                posts.author_id,
+                posts.status,
+                posts.published_at,
+                posts.created_at,
+                posts.updated_at,
+                posts.view_count,
+                users.username as author_name,
+                users.profile_image as author_image
+
+            FROM posts
+            LEFT JOIN users ON posts.author_id = users.id
+            WHERE $1::INTEGER IS NULL OR posts.author_id = $1
+            ORDER BY posts.updated_at DESC NULLS LAST
+            "#
+        )
+            .bind(author_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Posts without rendered HTML, as (id, content_format, content).
+    pub async fn find_unrendered(pool: &PgPool) -> Result<Vec<(i32, String, String)>, sqlx::Error> {
+        sqlx::query_as("SELECT id, content_format, content FROM posts WHERE content_html IS NULL ORDER BY id")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn set_content_html(pool: &PgPool, id: i32, html: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE posts SET content_html = $2 WHERE id = $1")
+            .bind(id)
+            .bind(html)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn find_post_by_id(pool: &PgPool, id: i32) -> Result<Option<Blogs>, sqlx::Error> {
+        sqlx::query_as::<_, Blogs>("SELECT * FROM posts WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn slug_taken(pool: &PgPool, slug: &str, except_id: Option<i32>) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM posts WHERE slug = $1 AND id IS DISTINCT FROM $2)"
+        )
+            .bind(slug)
+            .bind(except_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn create_post(pool: &PgPool, input: &PostInput, author_id: i32) -> Result<Blogs, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let post = sqlx::query_as::<_, Blogs>(
+            r#"
+            INSERT INTO posts (
+                title,
+                slug,
+                content,
+                excerpt,
+                featured_image,
+                author_id,
+                status,
+                published_at,
+                content_format,
+                content_html
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            )
+            RETURNING *
+            "#
+        )
+            .bind(&input.title)
+            .bind(&input.slug)
+            .bind(&input.content)
+            .bind(&input.excerpt)
+            .bind(&input.featured_image)
+            .bind(author_id)
+            .bind(&input.status)
+            .bind(input.published_at)
+            .bind(&input.content_format)
+            .bind(&input.content_html)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        set_categories(&mut tx, post.id.unwrap_or_default(), &input.category_ids).await?;
+        tx.commit().await?;
+
+        Ok(post)
+    }
+
+    pub async fn update_post(pool: &PgPool, id: i32, input: &PostInput) -> Result<Option<Blogs>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let post = sqlx::query_as::<_, Blogs>(
+            r#"
+            UPDATE posts SET
+                title = $2,
+                slug = $3,
+                content = $4,
+                excerpt = $5,
+                featured_image = $6,
+                status = $7,
+                published_at = $8,
+                content_format = $9,
+                content_html = $10,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+            .bind(id)
+            .bind(&input.title)
+            .bind(&input.slug)
+            .bind(&input.content)
+            .bind(&input.excerpt)
+            .bind(&input.featured_image)
+            .bind(&input.status)
+            .bind(input.published_at)
+            .bind(&input.content_format)
+            .bind(&input.content_html)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if post.is_some() {
+            set_categories(&mut tx, id, &input.category_ids).await?;
         }
+        tx.commit().await?;
 
         Ok(post)
     }
 
+    /// Changes only the status. Publishing stamps `published_at` with the current
+    /// time unless the post already has a date in the past.
+    pub async fn set_status(pool: &PgPool, id: i32, status: &str) -> Result<Option<Blogs>, sqlx::Error> {
+        sqlx::query_as::<_, Blogs>(
+            r#"
+            UPDATE posts SET
+                status = $2,
+                published_at = CASE
+                    WHEN $2 = 'published' AND (published_at IS NULL OR published_at > CURRENT_TIMESTAMP)
+                        THEN CURRENT_TIMESTAMP
+                    ELSE published_at
+                END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+            .bind(id)
+            .bind(status)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Deletes a post and its category links. Comments cascade in the schema.
+    pub async fn delete_post(pool: &PgPool, id: i32) -> Result<Option<Blogs>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM categories_relationships WHERE post_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let post = sqlx::query_as::<_, Blogs>("DELETE FROM posts WHERE id = $1 RETURNING *")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(post)
+    }
 
+    /// Publishes every scheduled post whose time has come and returns their slugs.
+    pub async fn publish_due(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            UPDATE posts SET
+                status = 'published',
+                updated_at = CURRENT_TIMESTAMP
+            WHERE status = 'scheduled' AND published_at <= CURRENT_TIMESTAMP
+            RETURNING slug
+            "#
+        )
+            .fetch_all(pool)
+            .await
+    }
 
+    /// Adds `views[i]` to the view count of post `ids[i]`, and to today's row
+    /// in `post_views_daily`. Leaves `updated_at` alone, since feeds and
+    /// sitemaps read it as the content change time.
+    pub async fn add_views(pool: &PgPool, ids: &[i32], views: &[i64]) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            WITH updated AS (
+                UPDATE posts SET view_count = COALESCE(posts.view_count, 0) + counts.views
+                FROM UNNEST($1::INTEGER[], $2::BIGINT[]) AS counts(id, views)
+                WHERE posts.id = counts.id
+                RETURNING posts.id, counts.views
+            )
+            INSERT INTO post_views_daily (post_id, day, views)
+            SELECT id, CURRENT_DATE, views FROM updated
+            ON CONFLICT (post_id, day) DO UPDATE SET views = post_views_daily.views + EXCLUDED.views
+            "#
+        )
+            .bind(ids)
+            .bind(views)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
 
-}
\ No newline at end of file
+    pub async fn count_by_status(pool: &PgPool) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as::<_, (String, i64)>(
+            "SELECT status, COUNT(*) FROM posts GROUP BY status ORDER BY status"
+        )
+            .fetch_all(pool)
+            .await
+    }
+}
+
+async fn set_categories(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    post_id: i32,
+    category_ids: &[i32],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM categories_relationships WHERE post_id = $1")
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO categories_relationships (term_id, post_id)
+        SELECT id, $2 FROM categories WHERE id = ANY($1)
+        "#
+    )
+        .bind(category_ids)
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+impl Category {
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<Category>, sqlx::Error> {
+        sqlx::query_as::<_, Category>(
+            "SELECT id, name, slug, description FROM categories ORDER BY name"
+        )
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_by_slug(pool: &PgPool, slug: &str) -> Result<Option<Category>, sqlx::Error> {
+        sqlx::query_as::<_, Category>(
+            "SELECT id, name, slug, description FROM categories WHERE slug = $1"
+        )
+            .bind(slug)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Every category with the number of published posts in it.
+    pub async fn find_all_with_counts(pool: &PgPool) -> Result<Vec<CategoryWithCount>, sqlx::Error> {
+        sqlx::query_as::<_, CategoryWithCount>(
+            r#"
+            SELECT
+                categories.id,
+                categories.name,
+                categories.slug,
+                categories.description,
+                COUNT(posts.id) as post_count
+            FROM categories
+            LEFT JOIN categories_relationships ON categories_relationships.term_id = categories.id
+            LEFT JOIN posts ON posts.id = categories_relationships.post_id
+                AND posts.status = 'published' AND posts.published_at <= NOW()
+            GROUP BY categories.id
+            ORDER BY categories.name
+            "#
+        )
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn find_ids_for_post(pool: &PgPool, post_id: i32) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar("SELECT term_id FROM categories_relationships WHERE post_id = $1")
+            .bind(post_id)
+            .fetch_all(pool)
+            .await
+    }
+}
@@ -2,10 +2,10 @@ use sqlx::{postgres::{PgPool, PgPoolOptions}};
 use sqlx::Row;
 use dotenv::dotenv;
 use std::fmt;
-use html_escape::encode_text;
 
 use std::{env, time::Duration};
 use std::error::Error;
+use crate::models::migrate::{self, MigrationError};
 use argon2::{
     password_hash::{
         rand_core::OsRng,
@@ -18,18 +18,20 @@ use argon2::{
 pub enum DatabaseError {
     ConnectionError(String),
     InitializationError(String),
+    Migration(MigrationError),
 }
 impl fmt::Display for DatabaseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            DatabaseError::ConnectionError(_) => write!(f, "Database connection error occurred"),
-            DatabaseError::InitializationError(_) => write!(f, "Database initialization error occurred"),
+            DatabaseError::ConnectionError(e) => write!(f, "Database connection error occurred: {}", e),
+            DatabaseError::InitializationError(e) => write!(f, "Database initialization error occurred: {}", e),
+            DatabaseError::Migration(e) => write!(f, "{}", e),
         }
     }
 }
 impl std::error::Error for DatabaseError {}
 
-pub async fn init_db() -> Result<PgPool, DatabaseError> {
+pub async fn connect() -> Result<PgPool, DatabaseError> {
     dotenv().ok();
 
     let database_url = env::var("DATABASE_URL")
@@ -60,6 +62,28 @@ pub async fn init_db() -> Result<PgPool, DatabaseError> {
         println!("Database connection established!");
     }
 
+    Ok(pool)
+}
+
+pub async fn init_db() -> Result<PgPool, DatabaseError> {
+    let pool = connect().await?;
+
+    // AUTO_MIGRATE=false leaves schema changes to `blogprojesi migrate up`;
+    // the server still refuses to start against an out-of-date schema.
+    let auto_migrate = env::var("AUTO_MIGRATE")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true);
+
+    let migrated = if auto_migrate {
+        migrate::run_pending(&pool).await.map(|_| ())
+    } else {
+        migrate::check_up_to_date(&pool).await
+    };
+    if let Err(e) = migrated {
+        pool.close().await;
+        return Err(DatabaseError::Migration(e));
+    }
+
     match initialize_tables(&pool).await {
         Ok(_) => {
             if cfg!(debug_assertions) {
@@ -79,20 +103,7 @@ pub async fn init_db() -> Result<PgPool, DatabaseError> {
     }
 }
 
-fn log_error(error: &str) {
-    if cfg!(debug_assertions) {
-        println!("Error: {}", error);
-    } else {
-        // will be added in future versions
-
-    }
-}
 async fn initialize_tables(pool: &PgPool) -> Result<(), Box<dyn Error>> {
-    create_users_table(pool).await?;
-    create_posts_table(pool).await?;
-    create_categories_table(pool).await?;
-    create_categories_relationships_table(pool).await?;
-    create_comments_table(pool).await?;
     // Test data creation
     ensure_test_user(pool).await?;
     ensure_test_post(pool).await?;
@@ -172,8 +183,6 @@ async fn ensure_test_post(pool: &PgPool) -> Result<(), sqlx::Error> {
             .await?
             .get("id");
 
-        // Create Test Post
-        let content = encode_text("<p>Merhaba, ben Halil Baki Şener. <br><br> Bu platform, modern web teknolojileri ve Rust programlama dilinin gücünü bir araya getiren kişisel blog projemdir. <b>Rust</b>'ın güvenli, performanslı ve eşzamanlı programlama özelliklerinden yararlanarak geliştirilmiştir.<br><br> Projede kullanılan teknolojiler: <br>- Rust ve Actix-web framework'ü ile güçlü bir backend altyapısı<br>- PostgreSQL veritabanı (Docker konteyner üzerinde)<br>- Askama template engine ile dinamik içerik yönetimi<br>- SQLx ile type-safe veritabanı işlemleri<br>- Modern güvenlik standartları için Argon2 şifreleme<br>- Tokio runtime ile asenkron işlem yönetimi<br><br> Bu proje, modern web geliştirme pratiklerini ve Rust ekosisteminin sunduğu güçlü araçları keşfetme yolculuğumun bir parçasıdır. Sürekli geliştirilmeye ve yeni özellikler eklenmeye devam edilmektedir.</p>");
 
         let post1_id: i32 = sqlx::query_scalar(
             r#"
@@ -368,153 +377,3 @@ async fn ensure_test_post(pool: &PgPool) -> Result<(), sqlx::Error> {
 
     Ok(())
 }
-
-
-async fn create_users_table(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let table_exists = check_table_exists(pool, "users").await?;
-
-    if !table_exists {
-        println!("Creating users table...");
-        sqlx::query(
-            r#"
-            CREATE TABLE users (
-                id SERIAL PRIMARY KEY,
-                username VARCHAR(50) UNIQUE NOT NULL,
-                email VARCHAR(255) UNIQUE NOT NULL,
-                password_hash VARCHAR(255) NOT NULL,
-                full_name VARCHAR(100),
-                bio TEXT,
-                profile_image VARCHAR(255),
-                role VARCHAR(20) NOT NULL DEFAULT 'user',
-                created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-            .execute(pool)
-            .await?;
-        println!("Users table created successfully!");
-    }
-    Ok(())
-}
-async fn create_posts_table(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let table_exists = check_table_exists(pool, "posts").await?;
-
-    if !table_exists {
-        println!("Creating posts table...");
-        sqlx::query(
-            r#"
-            CREATE TABLE posts (
-                id SERIAL PRIMARY KEY,
-                title VARCHAR(255) NOT NULL,
-                slug VARCHAR(255) UNIQUE NOT NULL,
-                content TEXT NOT NULL,
-                excerpt TEXT,
-                featured_image VARCHAR(255),
-                author_id INTEGER REFERENCES users(id) NOT NULL,
-                status VARCHAR(20) NOT NULL DEFAULT 'draft',
-                published_at TIMESTAMPTZ,
-                created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-                view_count INTEGER DEFAULT 0
-            )
-            "#,
-        )
-            .execute(pool)
-            .await?;
-        println!("Posts table created successfully!");
-    }
-    Ok(())
-}
-async fn create_categories_table(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let table_exists = check_table_exists(pool, "categories").await?;
-
-    if !table_exists {
-        println!("Creating categories table...");
-        sqlx::query(
-            r#"
-            CREATE TABLE categories (
-                id SERIAL PRIMARY KEY,
-                name VARCHAR(50) NOT NULL,
-                slug VARCHAR(50) UNIQUE NOT NULL,
-                description TEXT,
-                created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-            .execute(pool)
-            .await?;
-        println!("Categories table created successfully!");
-    }
-    Ok(())
-}
-
-async fn create_categories_relationships_table(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let table_exists = check_table_exists(pool, "categories_relationships").await?;
-
-    if !table_exists {
-        println!("Creating categories_relationships table...");
-        sqlx::query(
-            r#"
-            CREATE TABLE categories_relationships (
-                id SERIAL PRIMARY KEY,
-                term_id INTEGER NOT NULL REFERENCES categories(id),
-                post_id INTEGER NOT NULL REFERENCES posts(id)
-            )
-            "#,
-        )
-            .execute(pool)
-            .await?;
-        println!("Categories relationships table created successfully!");
-    }
-    Ok(())
-}
-
-
-
-async fn create_comments_table(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let table_exists = check_table_exists(pool, "comments").await?;
-
-    if !table_exists {
-        println!("Creating comments table...");
-        sqlx::query(
-            r#"
-            CREATE TABLE comments (
-                id SERIAL PRIMARY KEY,
-                post_id INTEGER REFERENCES posts(id) ON DELETE CASCADE,
-                user_id INTEGER REFERENCES users(id),
-                parent_id INTEGER REFERENCES comments(id),
-                content TEXT NOT NULL,
-                author_name VARCHAR(100),
-                author_email VARCHAR(255),
-                status VARCHAR(20) NOT NULL DEFAULT 'pending',
-                created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-                updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-            .execute(pool)
-            .await?;
-        println!("Comments table created successfully!");
-    }
-    Ok(())
-}
-
-
-async fn check_table_exists(pool: &PgPool, table_name: &str) -> Result<bool, sqlx::Error> {
-    let exists = sqlx::query(
-        r#"
-        SELECT EXISTS (
-            SELECT FROM information_schema.tables
-            WHERE table_schema = 'public'
-            AND table_name = $1
-        );
-        "#,
-    )
-        .bind(table_name)
-        .fetch_one(pool)
-        .await?
-        .get::<bool, _>(0);
-
-    Ok(exists)
-}
\ No newline at end of file
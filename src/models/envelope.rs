@@ -0,0 +1,131 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::error::ErrorCode;
+use crate::models::blogs::HomePageTemp;
+use crate::models::pagination::Pagination;
+
+/// `code` of an error response (other than not found).
+pub const CODE_ERROR: i32 = 0;
+/// `code` of a response that found or did what was asked.
+pub const CODE_FOUND: i32 = 1;
+/// `code` of an empty result or a missing resource.
+pub const CODE_EMPTY: i32 = 2;
+
+/// The `{status, code, message, data}` shape every `/api` response uses.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Envelope<T> {
+    pub status: bool,
+    pub code: i32,
+    pub message: String,
+    pub data: T,
+}
+
+impl<T> Envelope<T> {
+    pub fn found(message: &str, data: T) -> Self {
+        Envelope { status: true, code: CODE_FOUND, message: message.to_string(), data }
+    }
+
+    pub fn empty(message: &str, data: T) -> Self {
+        Envelope { status: false, code: CODE_EMPTY, message: message.to_string(), data }
+    }
+}
+
+/// Envelope of an acknowledgement without data.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct MessageEnvelope {
+    pub status: bool,
+    pub code: i32,
+    pub message: String,
+}
+
+impl MessageEnvelope {
+    pub fn ok(message: &str) -> Self {
+        MessageEnvelope { status: true, code: CODE_FOUND, message: message.to_string() }
+    }
+}
+
+/// Envelope of every error. `error` is meant for programs and stays the same
+/// across releases; `message` is for people and may change.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ErrorEnvelope {
+    pub status: bool,
+    /// 2 for not found, 0 otherwise.
+    pub code: i32,
+    pub message: String,
+    pub error: ErrorCode,
+    /// Only sent with `validation_failed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<ValidationErrors>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PageInfo {
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+    pub total_pages: i64,
+}
+
+impl From<&Pagination> for PageInfo {
+    fn from(pagination: &Pagination) -> Self {
+        PageInfo {
+            page: pagination.page,
+            per_page: pagination.per_page,
+            total: pagination.total,
+            total_pages: pagination.total_pages(),
+        }
+    }
+}
+
+/// `data` of the paginated post listings.
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PostList {
+    pub posts: Vec<HomePageTemp>,
+    pub pagination: PageInfo,
+}
+
+/// `data` of a 422 response: one message per problem, and the same messages
+/// grouped by request field when they belong to one.
+#[derive(Serialize, Debug, Clone, Default, ToSchema)]
+pub struct ValidationErrors {
+    pub errors: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        let message = message.into();
+        self.errors.push(message.clone());
+        self.fields.entry(field.to_string()).or_default().push(message);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl From<Vec<String>> for ValidationErrors {
+    fn from(errors: Vec<String>) -> Self {
+        ValidationErrors { errors, fields: BTreeMap::new() }
+    }
+}
+
+impl From<validator::ValidationErrors> for ValidationErrors {
+    fn from(report: validator::ValidationErrors) -> Self {
+        let mut errors = ValidationErrors::default();
+        // Sorted, so the messages come out in the same order every time.
+        let fields: BTreeMap<_, _> = report.field_errors().into_iter().collect();
+        for (field, field_errors) in fields {
+            for error in field_errors {
+                match &error.message {
+                    Some(message) => errors.add(&field, message.to_string()),
+                    None => errors.add(&field, format!("{} is invalid", field)),
+                }
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::postgres::PgPool;
+
+/// A published post as it appears in the feeds, with its category names.
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+pub struct FeedEntry {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub content: Option<String>,
+    pub excerpt: Option<String>,
+    pub featured_image: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub author_name: String,
+    pub author_image: Option<String>,
+    pub categories: Vec<String>,
+}
+
+impl FeedEntry {
+    /// The newest published posts, optionally limited to one category.
+    pub async fn find_recent(
+        pool: &PgPool,
+        category_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>, sqlx::Error> {
+        sqlx::query_as::<_, FeedEntry>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                COALESCE(posts.content_html, '') as content,
+                posts.excerpt,
+                posts.featured_image,
+                posts.published_at,
+                GREATEST(COALESCE(posts.updated_at, posts.published_at), posts.published_at) as updated_at,
+                users.username as author_name,
+                users.profile_image as author_image,
+                ARRAY(
+                    SELECT categories.name
+                    FROM categories
+                    INNER JOIN categories_relationships ON categories_relationships.term_id = categories.id
+                    WHERE categories_relationships.post_id = posts.id
+                    ORDER BY categories.name
+                ) as categories
+
+            FROM posts
+            INNER JOIN users ON posts.author_id = users.id
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            AND ($1::INTEGER IS NULL OR EXISTS(
+                SELECT 1 FROM categories_relationships
+                WHERE categories_relationships.post_id = posts.id
+                AND categories_relationships.term_id = $1
+            ))
+            ORDER BY posts.published_at DESC, posts.id DESC
+            LIMIT $2
+            "#
+        )
+            .bind(category_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+}
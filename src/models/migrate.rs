@@ -0,0 +1,262 @@
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgConnection, PgPool, Postgres};
+use sqlx::{Connection, Row};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+// Arbitrary key for pg_advisory_lock so two servers starting at once
+// don't apply the same migration twice.
+const MIGRATION_LOCK_KEY: i64 = 0x0062_6c6f_676d_6967;
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../../migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+// Ordered list of every migration the binary knows about.
+// New migrations are appended here, never inserted in the middle.
+pub static MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_initial_schema"),
+    migration!(2, "0002_sessions"),
+    migration!(3, "0003_post_search"),
+    migration!(4, "0004_scheduled_posts"),
+    migration!(5, "0005_content_format"),
+    migration!(6, "0006_raw_comments"),
+    migration!(7, "0007_post_views_daily"),
+    migration!(8, "0008_post_navigation"),
+    migration!(9, "0009_api_tokens"),
+    migration!(10, "0010_rerender_content"),
+];
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(sqlx::Error),
+    // The database has a version applied that this binary doesn't ship.
+    UnknownVersion(i64),
+    ChecksumMismatch(i64),
+    Pending(Vec<i64>),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrationError::Database(e) => write!(f, "Migration database error: {}", e),
+            MigrationError::UnknownVersion(v) => write!(
+                f,
+                "Database has migration {} applied which this binary does not know about; refusing to start",
+                v
+            ),
+            MigrationError::ChecksumMismatch(v) => write!(
+                f,
+                "Migration {} was modified after being applied (checksum mismatch)",
+                v
+            ),
+            MigrationError::Pending(versions) => write!(
+                f,
+                "Pending migrations {:?}; run `blogprojesi migrate up` or set AUTO_MIGRATE=true",
+                versions
+            ),
+        }
+    }
+}
+impl std::error::Error for MigrationError {}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(e: sqlx::Error) -> Self {
+        MigrationError::Database(e)
+    }
+}
+
+struct AppliedMigration {
+    version: i64,
+    checksum: String,
+}
+
+async fn ensure_migrations_table(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            checksum CHAR(64) NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn applied_migrations(conn: &mut PgConnection) -> Result<Vec<AppliedMigration>, sqlx::Error> {
+    let rows = sqlx::query("SELECT version, checksum FROM schema_migrations ORDER BY version")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AppliedMigration {
+            version: row.get("version"),
+            checksum: row.get::<String, _>("checksum").trim().to_string(),
+        })
+        .collect())
+}
+
+// Checks that every applied migration is known and unchanged, and returns
+// the versions that still have to be applied.
+fn verify(applied: &[AppliedMigration]) -> Result<Vec<&'static Migration>, MigrationError> {
+    for record in applied {
+        match MIGRATIONS.iter().find(|m| m.version == record.version) {
+            Some(migration) if migration.checksum() != record.checksum => {
+                return Err(MigrationError::ChecksumMismatch(record.version));
+            }
+            Some(_) => {}
+            None => return Err(MigrationError::UnknownVersion(record.version)),
+        }
+    }
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .collect())
+}
+
+async fn lock(pool: &PgPool) -> Result<PoolConnection<Postgres>, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+    ensure_migrations_table(&mut conn).await?;
+    Ok(conn)
+}
+
+async fn unlock(mut conn: PoolConnection<Postgres>) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn apply(conn: &mut PgConnection, migration: &Migration) -> Result<(), sqlx::Error> {
+    println!("Applying migration {} ({})...", migration.version, migration.name);
+    let mut tx = conn.begin().await?;
+    sqlx::raw_sql(migration.up).execute(&mut *tx).await?;
+    sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)")
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(migration.checksum())
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+async fn revert(conn: &mut PgConnection, migration: &Migration) -> Result<(), sqlx::Error> {
+    println!("Reverting migration {} ({})...", migration.version, migration.name);
+    let mut tx = conn.begin().await?;
+    sqlx::raw_sql(migration.down).execute(&mut *tx).await?;
+    sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+        .bind(migration.version)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+/// Applies every pending migration in order. Returns the applied versions.
+pub async fn run_pending(pool: &PgPool) -> Result<Vec<i64>, MigrationError> {
+    let mut conn = lock(pool).await?;
+    let result = run_pending_locked(&mut conn).await;
+    unlock(conn).await?;
+    result
+}
+
+async fn run_pending_locked(conn: &mut PgConnection) -> Result<Vec<i64>, MigrationError> {
+    let applied = applied_migrations(conn).await?;
+    let pending = verify(&applied)?;
+
+    let mut versions = Vec::new();
+    for migration in pending {
+        apply(conn, migration).await?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+/// Fails unless the database is exactly at the binary's latest migration.
+pub async fn check_up_to_date(pool: &PgPool) -> Result<(), MigrationError> {
+    let mut conn = lock(pool).await?;
+    let result = applied_migrations(&mut conn).await;
+    unlock(conn).await?;
+
+    let pending = verify(&result?)?;
+    if pending.is_empty() {
+        Ok(())
+    } else {
+        Err(MigrationError::Pending(pending.iter().map(|m| m.version).collect()))
+    }
+}
+
+/// Reverts the last `steps` applied migrations, newest first.
+pub async fn rollback(pool: &PgPool, steps: usize) -> Result<Vec<i64>, MigrationError> {
+    let mut conn = lock(pool).await?;
+    let result = rollback_locked(&mut conn, steps).await;
+    unlock(conn).await?;
+    result
+}
+
+async fn rollback_locked(conn: &mut PgConnection, steps: usize) -> Result<Vec<i64>, MigrationError> {
+    let applied = applied_migrations(conn).await?;
+    verify(&applied)?;
+
+    let mut versions = Vec::new();
+    for record in applied.iter().rev().take(steps) {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.version == record.version)
+            .ok_or(MigrationError::UnknownVersion(record.version))?;
+        revert(conn, migration).await?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+/// Prints every known migration with whether it has been applied.
+pub async fn print_status(pool: &PgPool) -> Result<(), MigrationError> {
+    let mut conn = lock(pool).await?;
+    let result = applied_migrations(&mut conn).await;
+    unlock(conn).await?;
+    let applied = result?;
+
+    for migration in MIGRATIONS {
+        let state = match applied.iter().find(|a| a.version == migration.version) {
+            Some(a) if a.checksum != migration.checksum() => "applied (checksum mismatch)",
+            Some(_) => "applied",
+            None => "pending",
+        };
+        println!("{:04} {:<40} {}", migration.version, migration.name, state);
+    }
+    for record in applied.iter().filter(|a| !MIGRATIONS.iter().any(|m| m.version == a.version)) {
+        println!("{:04} {:<40} unknown to this binary", record.version, "?");
+    }
+    Ok(())
+}
@@ -1,5 +1,15 @@
+pub mod api_tokens;
 pub mod blogs;
 pub mod db;
+pub mod envelope;
+pub mod feeds;
+pub mod migrate;
+pub mod pagination;
+pub mod popular;
+pub mod search;
+pub mod sessions;
+pub mod sitemap;
+pub mod users;
 pub(crate) mod api;
 
 pub use blogs::Blogs;
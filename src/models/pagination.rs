@@ -0,0 +1,84 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    pub page: Option<i64>,
+}
+
+impl PageQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+}
+
+/// Position in a paginated listing, with the links the templates need.
+/// `base_path` is the listing URL without the `page` parameter.
+#[derive(Debug, Clone)]
+pub struct Pagination {
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+    pub base_path: String,
+}
+
+impl Pagination {
+    pub fn new(page: i64, per_page: i64, total: i64, base_path: impl Into<String>) -> Self {
+        Pagination {
+            page: page.max(1),
+            per_page: per_page.max(1),
+            total,
+            base_path: base_path.into(),
+        }
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+
+    pub fn total_pages(&self) -> i64 {
+        ((self.total + self.per_page - 1) / self.per_page).max(1)
+    }
+
+    pub fn has_prev(&self) -> bool {
+        self.page > 1
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page < self.total_pages()
+    }
+
+    // Requests past the last page render an empty listing; handlers use this to 404.
+    pub fn is_out_of_range(&self) -> bool {
+        self.page > self.total_pages()
+    }
+
+    pub fn page_url(&self, page: i64) -> String {
+        if page <= 1 {
+            self.base_path.clone()
+        } else {
+            // `base_path` may already carry filter parameters.
+            let separator = if self.base_path.contains('?') { '&' } else { '?' };
+            format!("{}{}page={}", self.base_path, separator, page)
+        }
+    }
+
+    pub fn prev_url(&self) -> String {
+        self.page_url(self.page - 1)
+    }
+
+    pub fn next_url(&self) -> String {
+        self.page_url(self.page + 1)
+    }
+
+    /// Value for the `Link` response header, if there is a neighbouring page.
+    pub fn link_header(&self) -> Option<String> {
+        let mut links = Vec::new();
+        if self.has_prev() {
+            links.push(format!("<{}>; rel=\"prev\"", self.prev_url()));
+        }
+        if self.has_next() {
+            links.push(format!("<{}>; rel=\"next\"", self.next_url()));
+        }
+        if links.is_empty() { None } else { Some(links.join(", ")) }
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPool;
+use utoipa::{IntoParams, ToSchema};
+
+/// How far back a popular posts list looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PopularWindow {
+    /// Total views since the post was published (`view_count`).
+    AllTime,
+    /// Views in the last `n` days, today included (`post_views_daily`).
+    Days(i32),
+}
+
+pub const MAX_WINDOW_DAYS: i32 = 365;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PopularQuery {
+    /// `all` (default) or a number of days such as `7d`.
+    pub window: Option<String>,
+}
+
+impl PopularQuery {
+    /// The requested window, `all` when missing; `None` if it can't be parsed.
+    pub fn window(&self) -> Option<PopularWindow> {
+        self.window.as_deref().map_or(Some(PopularWindow::AllTime), PopularWindow::parse)
+    }
+}
+
+impl PopularWindow {
+    /// Parses `all` or `<n>d`, e.g. `7d`.
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "all" {
+            return Some(PopularWindow::AllTime);
+        }
+        let days = value.strip_suffix('d')?.parse::<i32>().ok()?;
+        (1..=MAX_WINDOW_DAYS).contains(&days).then_some(PopularWindow::Days(days))
+    }
+}
+
+/// A published post in a "most read" or "trending" list.
+#[derive(Serialize, Deserialize, Debug, Clone, sqlx::FromRow, ToSchema)]
+pub struct PopularPost {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub featured_image: Option<String>,
+    pub published_at: DateTime<Utc>,
+    // Views within the window.
+    pub views: i64,
+}
+
+impl PopularPost {
+    pub async fn find(pool: &PgPool, window: PopularWindow, limit: i64) -> Result<Vec<PopularPost>, sqlx::Error> {
+        match window {
+            PopularWindow::AllTime => Self::find_most_read(pool, limit).await,
+            PopularWindow::Days(days) => Self::find_trending(pool, days, limit).await,
+        }
+    }
+
+    async fn find_most_read(pool: &PgPool, limit: i64) -> Result<Vec<PopularPost>, sqlx::Error> {
+        sqlx::query_as::<_, PopularPost>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.featured_image,
+                posts.published_at,
+                posts.view_count::BIGINT as views
+            FROM posts
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            AND posts.view_count > 0
+            ORDER BY posts.view_count DESC, posts.published_at DESC
+            LIMIT $1
+            "#
+        )
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    async fn find_trending(pool: &PgPool, days: i32, limit: i64) -> Result<Vec<PopularPost>, sqlx::Error> {
+        sqlx::query_as::<_, PopularPost>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.featured_image,
+                posts.published_at,
+                SUM(post_views_daily.views)::BIGINT as views
+            FROM post_views_daily
+            INNER JOIN posts ON posts.id = post_views_daily.post_id
+            WHERE post_views_daily.day > CURRENT_DATE - $1::INTEGER
+            AND posts.status = 'published' AND posts.published_at <= NOW()
+            GROUP BY posts.id
+            ORDER BY views DESC, posts.published_at DESC
+            LIMIT $2
+            "#
+        )
+            .bind(days)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::postgres::PgPool;
+use utoipa::{IntoParams, ToSchema};
+use html_escape::{decode_html_entities, encode_text};
+
+// ts_headline wraps matches in these markers; the snippet is HTML-escaped
+// afterwards and only then are the markers turned into <mark> tags.
+const MATCH_START: &str = "\u{27E6}";
+const MATCH_END: &str = "\u{27E7}";
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
+pub struct SearchResult {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub excerpt: Option<String>,
+    pub featured_image: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub author_name: String,
+    pub author_image: Option<String>,
+    pub rank: f32,
+    // Escaped HTML with matches wrapped in <mark>.
+    pub snippet: String,
+}
+
+pub struct Search;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Search terms, in web search syntax.
+    pub q: Option<String>,
+    /// At most 50, default 20.
+    pub limit: Option<i64>,
+}
+
+impl SearchQuery {
+    pub fn terms(&self) -> &str {
+        self.q.as_deref().unwrap_or("").trim()
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT)
+    }
+}
+
+fn highlight(snippet: &str) -> String {
+    let text = decode_html_entities(snippet);
+    encode_text(&text)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+impl Search {
+    /// Makes `search_settings` match the configured text search configuration,
+    /// reindexing every post when it changes. Unknown configurations are rejected.
+    pub async fn sync_config(pool: &PgPool, config: &str) -> Result<(), sqlx::Error> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pg_ts_config WHERE cfgname = $1)")
+            .bind(config)
+            .fetch_one(pool)
+            .await?;
+        if !exists {
+            return Err(sqlx::Error::Configuration(
+                format!("Unknown text search configuration: {}", config).into(),
+            ));
+        }
+
+        let current: String = sqlx::query_scalar("SELECT config::TEXT FROM search_settings")
+            .fetch_one(pool)
+            .await?;
+        if current == config {
+            return Ok(());
+        }
+
+        println!("Switching search configuration from {} to {}...", current, config);
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE search_settings SET config = $1::REGCONFIG")
+            .bind(config)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE posts SET search_vector = posts_search_vector(title, excerpt, COALESCE(content_html, content), $1::REGCONFIG)"
+        )
+            .bind(config)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Published posts matching `query` (web search syntax: quotes, OR, -word),
+    /// best match first.
+    pub async fn posts(pool: &PgPool, config: &str, query: &str, limit: i64) -> Result<Vec<SearchResult>, sqlx::Error> {
+        let headline_options = format!(
+            "StartSel={}, StopSel={}, MaxWords=35, MinWords=15, MaxFragments=2, FragmentDelimiter=\" … \"",
+            MATCH_START, MATCH_END
+        );
+
+        let mut results = sqlx::query_as::<_, SearchResult>(
+            r#"
+            SELECT
+                posts.id,
+                posts.title,
+                posts.slug,
+                posts.excerpt,
+                posts.featured_image,
+                posts.published_at,
+                users.username as author_name,
+                users.profile_image as author_image,
+                ts_rank(posts.search_vector, query) as rank,
+                ts_headline($1::REGCONFIG, blog_strip_html(COALESCE(posts.content_html, posts.content)), query, $3) as snippet
+
+            FROM posts
+            CROSS JOIN websearch_to_tsquery($1::REGCONFIG, blog_search_normalize($2)) query
+            LEFT JOIN users ON posts.author_id = users.id
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            AND posts.search_vector @@ query
+            ORDER BY rank DESC, posts.published_at DESC NULLS LAST
+            LIMIT $4
+            "#
+        )
+            .bind(config)
+            .bind(query)
+            .bind(headline_options)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+        for result in &mut results {
+            result.snippet = highlight(&result.snippet);
+        }
+
+        Ok(results)
+    }
+}
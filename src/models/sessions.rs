@@ -0,0 +1,69 @@
+use chrono::{Duration, Utc};
+use sqlx::postgres::PgPool;
+use sha2::{Digest, Sha256};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+use crate::models::users::User;
+
+pub struct Session;
+
+// The cookie carries the raw token; the table only keeps its hash so a
+// leaked sessions table can't be replayed.
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+impl Session {
+    /// Creates a session for `user_id` and returns the raw cookie token.
+    pub async fn create(pool: &PgPool, user_id: i32, ttl: std::time::Duration) -> Result<String, sqlx::Error> {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        let expires_at = Utc::now() + Duration::from_std(ttl).unwrap_or(Duration::hours(24));
+
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (token_hash, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            "#
+        )
+            .bind(hash_token(&token))
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(pool)
+            .await?;
+
+        Ok(token)
+    }
+
+    pub async fn find_user(pool: &PgPool, token: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT users.*
+            FROM sessions
+            INNER JOIN users ON sessions.user_id = users.id
+            WHERE sessions.token_hash = $1
+            AND sessions.expires_at > CURRENT_TIMESTAMP
+            "#
+        )
+            .bind(hash_token(token))
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn delete(pool: &PgPool, token: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM sessions WHERE token_hash = $1")
+            .bind(hash_token(token))
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= CURRENT_TIMESTAMP")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
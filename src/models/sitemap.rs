@@ -0,0 +1,75 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::postgres::PgPool;
+
+/// One `<url>` of the sitemap. `path` is relative to the site root.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SitemapUrl {
+    pub path: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// One rendering of `/sitemap.xml` and its `/sitemap-{n}.xml` parts. It is
+/// cached as a whole, so the index never lists parts from another build.
+pub struct Sitemaps {
+    // `/sitemap.xml`: the only urlset, or the index of `parts`.
+    pub index: String,
+    // `/sitemap-1.xml` onwards; empty when everything fits in `index`.
+    pub parts: Vec<String>,
+}
+
+impl Sitemaps {
+    /// The file served at `/sitemap-{part}.xml`.
+    pub fn part(&self, part: usize) -> Option<&str> {
+        part.checked_sub(1).and_then(|i| self.parts.get(i)).map(String::as_str)
+    }
+}
+
+impl SitemapUrl {
+    /// `lastmod` in the W3C datetime format sitemaps expect.
+    pub fn lastmod_w3c(&self) -> Option<String> {
+        self.lastmod.map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    /// Every public page worth indexing: the listings, categories with at
+    /// least one published post, and every published post.
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<SitemapUrl>, sqlx::Error> {
+        let posts = sqlx::query_as::<_, SitemapUrl>(
+            r#"
+            SELECT
+                '/blog/' || slug as path,
+                COALESCE(updated_at, published_at) as lastmod
+            FROM posts
+            WHERE status = 'published' AND published_at <= NOW()
+            ORDER BY published_at DESC, id DESC
+            "#
+        )
+            .fetch_all(pool)
+            .await?;
+
+        let categories = sqlx::query_as::<_, SitemapUrl>(
+            r#"
+            SELECT
+                '/category/' || categories.slug as path,
+                MAX(COALESCE(posts.updated_at, posts.published_at)) as lastmod
+            FROM categories
+            INNER JOIN categories_relationships ON categories_relationships.term_id = categories.id
+            INNER JOIN posts ON posts.id = categories_relationships.post_id
+            WHERE posts.status = 'published' AND posts.published_at <= NOW()
+            GROUP BY categories.id
+            ORDER BY categories.name
+            "#
+        )
+            .fetch_all(pool)
+            .await?;
+
+        let latest = posts.iter().filter_map(|url| url.lastmod).max();
+        let mut urls = vec![
+            SitemapUrl { path: "/".to_string(), lastmod: latest },
+            SitemapUrl { path: "/blog".to_string(), lastmod: latest },
+            SitemapUrl { path: "/category".to_string(), lastmod: latest },
+        ];
+        urls.extend(categories);
+        urls.extend(posts);
+        Ok(urls)
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::postgres::PgPool;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, Clone)]
+pub struct User {
+    pub id: i32,
+    pub username: String,
+    pub email: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    pub full_name: Option<String>,
+    pub bio: Option<String>,
+    pub profile_image: Option<String>,
+    pub role: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl User {
+    pub async fn find_by_login(pool: &PgPool, login: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users
+            WHERE username = $1 OR lower(email) = lower($1)
+            LIMIT 1
+            "#
+        )
+            .bind(login)
+            .fetch_optional(pool)
+            .await
+    }
+}
+
+/// The public part of a staff member's profile, as served by `/api/authors/{username}`.
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow, ToSchema)]
+pub struct AuthorProfile {
+    pub id: i32,
+    pub username: String,
+    pub full_name: Option<String>,
+    pub bio: Option<String>,
+    pub profile_image: Option<String>,
+    pub role: String,
+    pub post_count: i64,
+}
+
+impl AuthorProfile {
+    /// Users with the `user` role can't write posts and aren't authors.
+    pub async fn find_by_username(pool: &PgPool, username: &str) -> Result<Option<AuthorProfile>, sqlx::Error> {
+        sqlx::query_as::<_, AuthorProfile>(
+            r#"
+            SELECT
+                users.id,
+                users.username,
+                users.full_name,
+                users.bio,
+                users.profile_image,
+                users.role,
+                (
+                    SELECT COUNT(*) FROM posts
+                    WHERE posts.author_id = users.id
+                    AND posts.status = 'published' AND posts.published_at <= NOW()
+                ) as post_count
+            FROM users
+            WHERE users.username = $1
+            AND users.role IN ('contributor', 'author', 'editor', 'admin')
+            "#
+        )
+            .bind(username)
+            .fetch_optional(pool)
+            .await
+    }
+}
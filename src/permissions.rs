@@ -0,0 +1,188 @@
+use crate::models::api_tokens::API_SCOPES;
+
+/// Roles stored in `users.role`. Anything unrecognised is a plain `User`,
+/// which can log in but has no access to the admin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    User,
+    Contributor,
+    Author,
+    Editor,
+    Admin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    AccessAdmin,
+    // Create posts and edit your own drafts. Contributors stop here and can
+    // only submit drafts for review.
+    EditOwnPosts,
+    PublishOwnPosts,
+    EditOthersPosts,
+    PublishOthersPosts,
+    ModerateComments,
+}
+
+// Scheduling a post is publishing it in advance, so it needs the same permission.
+fn is_publishing(status: &str) -> bool {
+    matches!(status, "published" | "scheduled")
+}
+
+impl Role {
+    pub fn parse(role: &str) -> Role {
+        match role {
+            "admin" => Role::Admin,
+            "editor" => Role::Editor,
+            "author" => Role::Author,
+            "contributor" => Role::Contributor,
+            _ => Role::User,
+        }
+    }
+
+    // Roles are strictly ordered, so each permission has a minimum role.
+    pub fn has(&self, permission: Permission) -> bool {
+        let required = match permission {
+            Permission::AccessAdmin | Permission::EditOwnPosts => Role::Contributor,
+            Permission::PublishOwnPosts => Role::Author,
+            Permission::EditOthersPosts
+            | Permission::PublishOthersPosts
+            | Permission::ModerateComments => Role::Editor,
+        };
+        *self >= required
+    }
+
+    /// Statuses this role may put a post into.
+    pub fn allowed_statuses(&self) -> &'static [&'static str] {
+        if self.has(Permission::PublishOwnPosts) {
+            &["draft", "pending", "scheduled", "published"]
+        } else if self.has(Permission::EditOwnPosts) {
+            &["draft", "pending"]
+        } else {
+            &[]
+        }
+    }
+
+    /// API token scopes this role may grant. A scope never adds permissions
+    /// the role doesn't have; it only narrows what a token can do.
+    pub fn allowed_scopes(&self) -> Vec<&'static str> {
+        API_SCOPES
+            .iter()
+            .copied()
+            .filter(|scope| match *scope {
+                "comments:moderate" => self.has(Permission::ModerateComments),
+                _ => self.has(Permission::EditOwnPosts),
+            })
+            .collect()
+    }
+
+    /// Whether `user_id` with this role may edit or delete a post by
+    /// `author_id`. Contributors lose access to their own post once it is published.
+    pub fn can_edit_post(&self, user_id: i32, author_id: i32, status: Option<&str>) -> bool {
+        if self.has(Permission::EditOthersPosts) {
+            return true;
+        }
+        if !self.has(Permission::EditOwnPosts) || user_id != author_id {
+            return false;
+        }
+        self.has(Permission::PublishOwnPosts) || !status.is_some_and(is_publishing)
+    }
+
+    /// Whether `user_id` with this role may move a post by `author_id` into
+    /// `status`. Someone else's post needs EditOthersPosts whatever the
+    /// status, so an author can't unpublish a colleague's post.
+    pub fn can_set_status(&self, user_id: i32, author_id: i32, status: &str) -> bool {
+        if !self.allowed_statuses().contains(&status) {
+            return false;
+        }
+        let own = user_id == author_id;
+        if !is_publishing(status) {
+            return own || self.has(Permission::EditOthersPosts);
+        }
+        self.has(Permission::PublishOthersPosts) || (self.has(Permission::PublishOwnPosts) && own)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ME: i32 = 1;
+    const OTHER: i32 = 2;
+
+    #[test]
+    fn parse_falls_back_to_user() {
+        assert_eq!(Role::parse("editor"), Role::Editor);
+        assert_eq!(Role::parse("Admin"), Role::User);
+        assert_eq!(Role::parse(""), Role::User);
+    }
+
+    #[test]
+    fn contributor_edits_only_own_unpublished_posts() {
+        let role = Role::Contributor;
+        assert!(role.can_edit_post(ME, ME, Some("draft")));
+        assert!(role.can_edit_post(ME, ME, Some("pending")));
+        assert!(role.can_edit_post(ME, ME, None));
+        assert!(!role.can_edit_post(ME, ME, Some("published")));
+        assert!(!role.can_edit_post(ME, ME, Some("scheduled")));
+        assert!(!role.can_edit_post(ME, OTHER, Some("draft")));
+    }
+
+    #[test]
+    fn author_edits_own_posts_in_any_status() {
+        let role = Role::Author;
+        assert!(role.can_edit_post(ME, ME, Some("draft")));
+        assert!(role.can_edit_post(ME, ME, Some("published")));
+        assert!(!role.can_edit_post(ME, OTHER, Some("draft")));
+        assert!(!role.can_edit_post(ME, OTHER, Some("published")));
+    }
+
+    #[test]
+    fn editor_and_admin_edit_everyone_s_posts() {
+        for role in [Role::Editor, Role::Admin] {
+            assert!(role.can_edit_post(ME, ME, Some("published")));
+            assert!(role.can_edit_post(ME, OTHER, Some("draft")));
+            assert!(role.can_edit_post(ME, OTHER, Some("published")));
+        }
+    }
+
+    #[test]
+    fn user_edits_nothing() {
+        assert!(!Role::User.can_edit_post(ME, ME, Some("draft")));
+        assert!(!Role::User.can_set_status(ME, ME, "draft"));
+    }
+
+    #[test]
+    fn contributor_cannot_publish_or_schedule() {
+        let role = Role::Contributor;
+        assert!(role.can_set_status(ME, ME, "draft"));
+        assert!(role.can_set_status(ME, ME, "pending"));
+        assert!(!role.can_set_status(ME, ME, "published"));
+        assert!(!role.can_set_status(ME, ME, "scheduled"));
+        assert!(!role.can_set_status(ME, OTHER, "draft"));
+    }
+
+    #[test]
+    fn author_sets_status_only_on_own_posts() {
+        let role = Role::Author;
+        assert!(role.can_set_status(ME, ME, "published"));
+        assert!(role.can_set_status(ME, ME, "scheduled"));
+        assert!(!role.can_set_status(ME, OTHER, "published"));
+        assert!(!role.can_set_status(ME, OTHER, "scheduled"));
+        assert!(!role.can_set_status(ME, OTHER, "draft"));
+        assert!(!role.can_set_status(ME, OTHER, "pending"));
+    }
+
+    #[test]
+    fn editor_and_admin_publish_anyone_s_posts() {
+        for role in [Role::Editor, Role::Admin] {
+            assert!(role.can_set_status(ME, OTHER, "published"));
+            assert!(role.can_set_status(ME, OTHER, "scheduled"));
+            assert!(role.can_set_status(ME, OTHER, "pending"));
+        }
+    }
+
+    #[test]
+    fn unknown_status_is_rejected() {
+        assert!(!Role::Admin.can_set_status(ME, ME, "archived"));
+    }
+}
@@ -0,0 +1,51 @@
+use moka::future::Cache;
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+/// Fixed-window request counter: each key may make `limit` requests per
+/// `window`, counted from its first request in the window.
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    // Start of the key's current window and the requests made in it.
+    hits: Cache<String, (Instant, u32)>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        RateLimiter {
+            limit,
+            window,
+            hits: Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(window)
+                .build(),
+        }
+    }
+
+    /// Counts a request for `key`. Over the limit, fails with
+    /// `AppError::RateLimited` carrying the time until the window ends.
+    pub async fn check(&self, key: &str) -> Result<(), AppError> {
+        let window = self.window;
+        let entry = self
+            .hits
+            .entry(key.to_string())
+            .and_upsert_with(|current| {
+                let now = Instant::now();
+                let hits = match current.map(|entry| *entry.value()) {
+                    Some((start, count)) if start.elapsed() < window => (start, count.saturating_add(1)),
+                    _ => (now, 1),
+                };
+                std::future::ready(hits)
+            })
+            .await;
+
+        let (start, count) = entry.into_value();
+        if count > self.limit {
+            Err(AppError::RateLimited { retry_after: window.saturating_sub(start.elapsed()) })
+        } else {
+            Ok(())
+        }
+    }
+}
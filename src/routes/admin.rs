@@ -0,0 +1,28 @@
+use actix_web::web;
+use crate::handlers::admin;
+
+pub fn admin_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .route("", web::get().to(admin::dashboard))
+            .route("/", web::get().to(admin::dashboard))
+            .route("/login", web::get().to(admin::login_page))
+            .route("/login", web::post().to(admin::login))
+            .route("/logout", web::post().to(admin::logout))
+            .service(
+                web::scope("/posts")
+                    .route("", web::get().to(admin::posts_list))
+                    .route("", web::post().to(admin::create_post))
+                    .route("/new", web::get().to(admin::new_post))
+                    .route("/{id}", web::post().to(admin::update_post))
+                    .route("/{id}/edit", web::get().to(admin::edit_post))
+                    .route("/{id}/status", web::post().to(admin::change_post_status))
+                    .route("/{id}/delete", web::post().to(admin::delete_post))
+            )
+            .route("/comments", web::get().to(admin::comments_queue))
+            .route("/comments/bulk", web::post().to(admin::comments_bulk))
+            .route("/tokens", web::get().to(admin::tokens_page))
+            .route("/tokens", web::post().to(admin::create_token))
+            .route("/tokens/{id}/delete", web::post().to(admin::delete_token))
+    );
+}
@@ -1,18 +1,74 @@
-use actix_web::{web, HttpResponse};
-use crate::handlers::api;
+use actix_web::http::Method;
+use actix_web::{web, HttpResponse, Route};
+use crate::handlers::{api, openapi};
+
+// Every route described by the OpenAPI document, as (method, path under /api).
+// Registration goes through this list and a test compares it with the
+// document, so a handler can't be routed without being documented or the
+// other way round.
+fn documented_routes() -> Vec<(Method, &'static str, Route)> {
+    vec![
+        (Method::GET, "/posts", web::to(api::list_posts)),
+        (Method::POST, "/posts", web::to(api::create_post)),
+        (Method::GET, "/posts/search", web::to(api::search_posts)),
+        (Method::GET, "/posts/popular", web::to(api::popular_posts)),
+        (Method::GET, "/posts/{slug}", web::to(api::post_by_slug)),
+        (Method::PUT, "/posts/{id}", web::to(api::replace_post)),
+        (Method::PATCH, "/posts/{id}", web::to(api::patch_post)),
+        (Method::DELETE, "/posts/{id}", web::to(api::delete_post)),
+        (Method::GET, "/categories", web::to(api::categories)),
+        (Method::GET, "/categories/{slug}/posts", web::to(api::category_posts)),
+        (Method::GET, "/authors/{username}", web::to(api::author)),
+        (Method::GET, "/comments/get/{post_id}", web::to(api::comments)),
+        (Method::POST, "/comments/add", web::to(api::addcomment)),
+        (Method::PATCH, "/comments/{id}", web::to(api::moderate_comment)),
+        (Method::DELETE, "/comments/{id}", web::to(api::delete_comment)),
+    ]
+}
 
 pub fn api_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api")
-           .route("/test", web::get().to(|| async {
-                HttpResponse::Ok().body("API Test Working!")
-            }))
-            .service(
+    let mut scope = web::scope("/api")
+        .app_data(web::QueryConfig::default().error_handler(api::query_error))
+        .app_data(web::JsonConfig::default().error_handler(api::json_error))
+        .route("/test", web::get().to(|| async {
+            HttpResponse::Ok().body("API Test Working!")
+        }))
+        .route("/openapi.json", web::get().to(openapi::openapi_json))
+        .route("/docs", web::get().to(openapi::docs));
+
+    for (method, path, route) in documented_routes() {
+        scope = scope.route(path, route.method(method));
+    }
+    cfg.service(scope);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use utoipa::OpenApi;
+
+    #[test]
+    fn documented_routes_match_the_openapi_document() {
+        let routed: BTreeSet<(String, String)> = documented_routes()
+            .into_iter()
+            .map(|(method, path, _)| (format!("/api{}", path), method.as_str().to_lowercase()))
+            .collect();
 
-                web::scope("/comments")
-                    .route("/get/{post_id}", web::get().to(api::comments))
-                    .route("/add", web::post().to(api::addcomment))
+        let spec = serde_json::to_value(openapi::ApiDoc::openapi()).unwrap();
+        let documented: BTreeSet<(String, String)> = spec["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .filter(|key| ["get", "post", "put", "patch", "delete"].contains(&key.as_str()))
+                    .map(move |method| (path.clone(), method.clone()))
+            })
+            .collect();
 
-            )
-    );
-}
\ No newline at end of file
+        assert_eq!(routed, documented);
+    }
+}
@@ -8,5 +8,8 @@ pub fn blog_routes(cfg: &mut web::ServiceConfig) {
             .route("/blog", web::get().to(blogs::blogs_page))
             .route("/blog/", web::get().to(blogs::blogs_page))
             .route("/blog/{slug}", web::get().to(blogs::postinfo))
+            .route("/search", web::get().to(blogs::search_page))
+            .route("/category", web::get().to(blogs::categories_page))
+            .route("/category/{slug}", web::get().to(blogs::category_page))
     );
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+use actix_web::web;
+use crate::handlers::feeds;
+
+pub fn feed_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/feed.xml", web::get().to(feeds::rss))
+        .route("/atom.xml", web::get().to(feeds::atom))
+        .route("/feed.json", web::get().to(feeds::json_feed))
+        .route("/category/{slug}/feed.xml", web::get().to(feeds::category_rss));
+}
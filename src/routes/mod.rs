@@ -1,24 +1,19 @@
+mod admin;
 mod api;
 mod blogs;
+mod feeds;
+mod sitemap;
 
-use actix_web::{web, HttpResponse};
-use askama::Template;
-
-#[derive(Template)]
-#[template(path = "errors/404.html")]
-struct NotFoundTemplate {}
-
-async fn not_found() -> HttpResponse {
-    let template = NotFoundTemplate {};
-    HttpResponse::NotFound()
-        .content_type("text/html")
-        .body(template.render().unwrap_or_else(|_| String::from("404 - Sayfa Bulunamadı")))
-}
+use actix_web::web;
+use crate::handlers::blogs::not_found;
 
 pub fn configure_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("")
+            .configure(admin::admin_routes)
             .configure(api::api_routes)
+            .configure(feeds::feed_routes)
+            .configure(sitemap::sitemap_routes)
             .configure(blogs::blog_routes)
     )
         .default_service(
@@ -0,0 +1,8 @@
+use actix_web::web;
+use crate::handlers::sitemap;
+
+pub fn sitemap_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route("/sitemap.xml", web::get().to(sitemap::sitemap))
+        .route("/sitemap-{part:\\d+}.xml", web::get().to(sitemap::sitemap_part))
+        .route("/robots.txt", web::get().to(sitemap::robots));
+}
@@ -0,0 +1,32 @@
+use actix_web::rt::time;
+
+use crate::error;
+use crate::models::Blogs;
+use crate::state::AppState;
+
+/// Background task that publishes `scheduled` posts once their `published_at`
+/// has passed, then refreshes their cached pages. Runs for the life of the server.
+pub async fn run(state: AppState) {
+    let mut interval = time::interval(state.config.scheduler_interval);
+    loop {
+        interval.tick().await;
+        publish_due(&state).await;
+    }
+}
+
+async fn publish_due(state: &AppState) {
+    let slugs = match Blogs::publish_due(&state.pool).await {
+        Ok(slugs) => slugs,
+        Err(e) => {
+            error::log_ignored(e);
+            return;
+        }
+    };
+
+    for slug in slugs {
+        println!("Published scheduled post: {}", slug);
+        if let Err(e) = state.refresh_post(&slug).await {
+            error::log_ignored(e);
+        }
+    }
+}
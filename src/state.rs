@@ -1,10 +1,56 @@
 use sqlx::PgPool;
 use moka::future::Cache;
 use std::sync::Arc;
-use crate::models::blogs::BlogWithAuthor;
+use crate::models::blogs::{BlogWithAuthor, Blogs};
+use crate::models::popular::{PopularPost, PopularWindow};
+use crate::models::sitemap::Sitemaps;
+use crate::config::Config;
+use crate::rate_limit::RateLimiter;
+use crate::views::ViewCounter;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub post_cache: Arc<Cache<String, BlogWithAuthor>>,
+    pub config: Arc<Config>,
+    // The rendered /sitemap.xml and /sitemap-{n}.xml, as one entry.
+    pub sitemap_cache: Arc<Cache<(), Arc<Sitemaps>>>,
+    pub views: Arc<ViewCounter>,
+    // Popular posts lists; a short TTL keeps them close to the view counts.
+    pub popular_cache: Arc<Cache<PopularWindow, Arc<Vec<PopularPost>>>>,
+    // Comment submissions per client IP.
+    pub comment_limiter: Arc<RateLimiter>,
+    // Write API requests per user id.
+    pub write_limiter: Arc<RateLimiter>,
+}
+
+impl AppState {
+    /// Drops the cached pages after a post was created, edited, deleted or
+    /// changed status. Every cached post goes, since a post also shows up in
+    /// the related lists of others.
+    pub fn invalidate_posts(&self) {
+        self.post_cache.invalidate_all();
+        self.sitemap_cache.invalidate_all();
+        self.popular_cache.invalidate_all();
+    }
+
+    /// Replaces the cached copy of a post with a fresh one, so the first
+    /// visitor after it goes live doesn't pay for the query.
+    pub async fn refresh_post(&self, slug: &str) -> Result<(), sqlx::Error> {
+        self.invalidate_posts();
+        if let Some(post) = Blogs::find_post_by_slug(&self.pool, slug, self.config.related_posts_limit).await? {
+            self.post_cache.insert(slug.to_string(), post).await;
+        }
+        Ok(())
+    }
+
+    /// The `popular_limit` most viewed posts within `window`, cached.
+    pub async fn popular_posts(&self, window: PopularWindow) -> Result<Arc<Vec<PopularPost>>, sqlx::Error> {
+        if let Some(posts) = self.popular_cache.get(&window).await {
+            return Ok(posts);
+        }
+        let posts = Arc::new(PopularPost::find(&self.pool, window, self.config.popular_limit).await?);
+        self.popular_cache.insert(window, posts.clone()).await;
+        Ok(posts)
+    }
 }
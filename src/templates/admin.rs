@@ -0,0 +1,90 @@
+use askama::Template;
+use crate::filters;
+use crate::models::api::ModerationComment;
+use crate::models::api_tokens::ApiToken;
+use crate::models::blogs::{Category, HomePageTemp, PostForm};
+use crate::models::users::User;
+use crate::permissions::Role;
+
+#[derive(Template)]
+#[template(path = "admin/login.html")]
+pub struct LoginTemplate {
+    pub username: String,
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "admin/dashboard.html")]
+pub struct DashboardTemplate {
+    pub user: User,
+    pub post_counts: Vec<(String, i64)>,
+    // Empty for roles that can't moderate comments.
+    pub comment_counts: Vec<(String, i64)>,
+}
+
+#[derive(Template)]
+#[template(path = "admin/posts.html")]
+pub struct PostsTemplate {
+    pub user: User,
+    pub role: Role,
+    pub posts: Vec<HomePageTemp>,
+    pub notice: Option<String>,
+}
+
+impl PostsTemplate {
+    pub fn can_edit(&self, post: &HomePageTemp) -> bool {
+        self.role.can_edit_post(self.user.id, post.author_id, post.status.as_deref())
+    }
+
+    pub fn can_publish(&self, post: &HomePageTemp) -> bool {
+        self.role.can_set_status(self.user.id, post.author_id, "published")
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/post_form.html")]
+pub struct PostFormTemplate {
+    pub user: User,
+    pub post_id: Option<i32>,
+    pub form: PostForm,
+    pub categories: Vec<Category>,
+    pub statuses: &'static [&'static str],
+    pub errors: Vec<String>,
+}
+
+impl PostFormTemplate {
+    pub fn has_category(&self, id: &i32) -> bool {
+        self.form.category_ids.contains(id)
+    }
+
+    pub fn is_status(&self, status: &str) -> bool {
+        self.form.status == status
+    }
+
+    pub fn is_format(&self, format: &str) -> bool {
+        self.form.content_format == format
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin/comments.html")]
+pub struct CommentsTemplate {
+    pub user: User,
+    pub status: String,
+    pub counts: Vec<(String, i64)>,
+    pub comments: Vec<ModerationComment>,
+    pub notice: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "admin/tokens.html")]
+pub struct TokensTemplate {
+    pub user: User,
+    pub tokens: Vec<ApiToken>,
+    // Scopes the user's role may grant.
+    pub scopes: Vec<&'static str>,
+    // Set right after creation; the only time the full token is shown.
+    pub new_token: Option<String>,
+    pub errors: Vec<String>,
+    pub notice: Option<String>,
+}
@@ -0,0 +1,8 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(path = "api/docs.html")]
+pub struct ApiDocsTemplate<'a> {
+    pub site_title: &'a str,
+    pub spec_url: &'a str,
+}
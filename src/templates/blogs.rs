@@ -1,22 +1,89 @@
 use askama::Template;
-use crate::models::blogs::{BlogWithAuthor, HomePageTemp};
+use std::sync::Arc;
+use crate::models::blogs::{AdjacentPost, BlogWithAuthor, Category, CategoryWithCount, HomePageTemp};
+use crate::models::pagination::Pagination;
+use crate::models::popular::PopularPost;
+use crate::models::search::SearchResult;
 use crate::filters;
 
+/// The "most read" and "trending" sidebar lists.
+pub struct PopularWidgets {
+    pub most_read: Arc<Vec<PopularPost>>,
+    pub trending: Arc<Vec<PopularPost>>,
+    pub trending_days: i32,
+}
+
+/// Previous/next links of a post page. With `category` set (`?in=slug`) they
+/// stay within that category.
+pub struct PostNavigation {
+    pub previous: Option<AdjacentPost>,
+    pub next: Option<AdjacentPost>,
+    pub category: Option<Category>,
+}
+
+impl PostNavigation {
+    pub fn url(&self, post: &AdjacentPost) -> String {
+        match &self.category {
+            Some(category) => format!("/blog/{}?in={}", post.slug, category.slug),
+            None => format!("/blog/{}", post.slug),
+        }
+    }
+}
+
 #[derive(Template)]
 #[template(path = "blog/home.html")]
 pub struct IndexTemplate {
     pub posts: Vec<HomePageTemp>,
+    pub pagination: Pagination,
+    pub popular: PopularWidgets,
 }
 
 #[derive(Template)]
 #[template(path = "blog/blogs.html")]
 pub struct BlogTemplate {
     pub posts: Vec<HomePageTemp>,
+    pub pagination: Pagination,
 }
 
 #[derive(Template)]
 #[template(path = "blog/post.html")]
 pub struct PostInfoTemplate {
     pub post: BlogWithAuthor,
+    pub comment_max_depth: usize,
+    pub popular: PopularWidgets,
+    pub navigation: PostNavigation,
+    pub canonical_url: String,
+}
+
+#[derive(Template)]
+#[template(path = "blog/search.html")]
+pub struct SearchTemplate {
+    pub query: String,
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Template)]
+#[template(path = "blog/category.html")]
+pub struct CategoryTemplate {
+    pub category: Category,
+    pub posts: Vec<HomePageTemp>,
+    pub pagination: Pagination,
+}
+
+#[derive(Template)]
+#[template(path = "blog/categories.html")]
+pub struct CategoriesTemplate {
+    pub categories: Vec<CategoryWithCount>,
 }
 
+#[derive(Template)]
+#[template(path = "errors/404.html")]
+pub struct NotFoundTemplate {}
+
+#[derive(Template)]
+#[template(path = "errors/error.html")]
+pub struct ErrorTemplate<'a> {
+    pub status: u16,
+    pub title: &'a str,
+    pub message: &'a str,
+}
@@ -0,0 +1,141 @@
+use askama::Template;
+use chrono::{DateTime, Utc};
+use html_escape::encode_text;
+use serde::Serialize;
+
+use crate::models::feeds::FeedEntry;
+
+/// Everything the RSS and Atom templates share. URLs are absolute.
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    pub site_url: String,
+    // The HTML page the feed mirrors.
+    pub html_url: String,
+    pub self_url: String,
+    pub full_content: bool,
+    pub entries: Vec<FeedEntry>,
+}
+
+impl Feed {
+    /// Newest `updated_at` among the entries; used for `Last-Modified` too.
+    pub fn updated(&self) -> Option<DateTime<Utc>> {
+        self.entries.iter().map(|entry| entry.updated_at).max()
+    }
+
+    pub fn post_url(&self, entry: &FeedEntry) -> String {
+        format!("{}/blog/{}", self.site_url, entry.slug)
+    }
+
+    pub fn absolute_url(&self, url: &str) -> String {
+        if url.starts_with('/') {
+            format!("{}{}", self.site_url, url)
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// Post body for the entry in full-content mode, with root-relative links
+    /// and images made absolute so they work in feed readers.
+    pub fn content(&self, entry: &FeedEntry) -> Option<String> {
+        if !self.full_content {
+            return None;
+        }
+        entry.content.as_ref().map(|html| {
+            html.replace("href=\"/", &format!("href=\"{}/", self.site_url))
+                .replace("src=\"/", &format!("src=\"{}/", self.site_url))
+        })
+    }
+}
+
+#[derive(Template)]
+#[template(path = "feeds/rss.xml")]
+pub struct RssTemplate {
+    pub feed: Feed,
+}
+
+#[derive(Template)]
+#[template(path = "feeds/atom.xml")]
+pub struct AtomTemplate {
+    pub feed: Feed,
+}
+
+/// JSON Feed 1.1 (https://jsonfeed.org/version/1.1).
+#[derive(Serialize)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    pub description: String,
+    pub language: &'static str,
+    pub items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub content_html: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    pub date_published: String,
+    pub date_modified: String,
+    pub authors: Vec<JsonFeedAuthor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct JsonFeedAuthor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+}
+
+impl From<Feed> for JsonFeed {
+    fn from(feed: Feed) -> Self {
+        let items = feed
+            .entries
+            .iter()
+            .map(|entry| {
+                // content_html is required; excerpt mode sends the excerpt as a paragraph.
+                let content_html = feed.content(entry).unwrap_or_else(|| {
+                    entry
+                        .excerpt
+                        .as_ref()
+                        .map(|excerpt| format!("<p>{}</p>", encode_text(excerpt)))
+                        .unwrap_or_default()
+                });
+                JsonFeedItem {
+                    id: feed.post_url(entry),
+                    url: feed.post_url(entry),
+                    title: entry.title.clone(),
+                    content_html,
+                    summary: entry.excerpt.clone(),
+                    image: entry.featured_image.as_deref().map(|url| feed.absolute_url(url)),
+                    date_published: entry.published_at.to_rfc3339(),
+                    date_modified: entry.updated_at.to_rfc3339(),
+                    authors: vec![JsonFeedAuthor {
+                        name: entry.author_name.clone(),
+                        avatar: entry.author_image.as_deref().map(|url| feed.absolute_url(url)),
+                    }],
+                    tags: entry.categories.clone(),
+                }
+            })
+            .collect();
+
+        JsonFeed {
+            version: "https://jsonfeed.org/version/1.1",
+            title: feed.title,
+            home_page_url: feed.html_url,
+            feed_url: feed.self_url,
+            description: feed.description,
+            language: "tr",
+            items,
+        }
+    }
+}
@@ -1 +1,5 @@
+pub mod admin;
+pub mod api;
 pub mod blogs;
+pub mod feeds;
+pub mod sitemap;
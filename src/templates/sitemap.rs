@@ -0,0 +1,18 @@
+use askama::Template;
+
+use crate::models::sitemap::SitemapUrl;
+
+#[derive(Template)]
+#[template(path = "sitemap/urlset.xml")]
+pub struct UrlSetTemplate<'a> {
+    pub site_url: &'a str,
+    pub urls: &'a [SitemapUrl],
+}
+
+#[derive(Template)]
+#[template(path = "sitemap/index.xml")]
+pub struct SitemapIndexTemplate<'a> {
+    pub site_url: &'a str,
+    // Numbers of the `/sitemap-{n}.xml` parts, starting at 1.
+    pub parts: Vec<usize>,
+}
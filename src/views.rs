@@ -0,0 +1,113 @@
+use actix_web::rt::time;
+use actix_web::HttpRequest;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use moka::future::Cache;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::error;
+use crate::models::Blogs;
+use crate::state::AppState;
+
+/// Counts post views in memory. A visitor (hash of IP and user agent) counts
+/// once per post per window; the totals are written to `posts.view_count` in
+/// batches by `run_flusher`.
+pub struct ViewCounter {
+    // Random per process, so the visitor hashes can't be reversed by
+    // hashing every IP address.
+    salt: [u8; 32],
+    seen: Cache<String, ()>,
+    pending: Mutex<HashMap<i32, i64>>,
+}
+
+impl ViewCounter {
+    pub fn new(window: Duration) -> Self {
+        let mut salt = [0u8; 32];
+        OsRng.fill_bytes(&mut salt);
+        ViewCounter {
+            salt,
+            seen: Cache::builder()
+                .max_capacity(1_000_000)
+                .time_to_live(window)
+                .build(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn visitor_key(&self, post_id: i32, ip: &str, user_agent: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+        hasher.update(post_id.to_be_bytes());
+        hasher.update(ip.as_bytes());
+        hasher.update([0]);
+        hasher.update(user_agent.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Records a view unless this visitor already viewed the post within the window.
+    pub async fn record(&self, post_id: i32, ip: &str, user_agent: &str) {
+        let key = self.visitor_key(post_id, ip, user_agent);
+        let entry = self.seen.entry(key).or_insert(()).await;
+        if !entry.is_fresh() {
+            return;
+        }
+        *self.pending.lock().unwrap().entry(post_id).or_insert(0) += 1;
+    }
+
+    fn take_pending(&self) -> HashMap<i32, i64> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+
+    // Puts counts back after a failed write so they go out with the next batch.
+    fn restore(&self, counts: HashMap<i32, i64>) {
+        let mut pending = self.pending.lock().unwrap();
+        for (post_id, count) in counts {
+            *pending.entry(post_id).or_insert(0) += count;
+        }
+    }
+}
+
+/// Crawlers would otherwise make up most of the numbers.
+pub fn is_bot(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_ascii_lowercase();
+    user_agent.is_empty()
+        || ["bot", "crawler", "spider", "slurp", "curl", "wget", "python-requests", "headless"]
+            .iter()
+            .any(|marker| user_agent.contains(marker))
+}
+
+/// The client's IP address, from the proxy headers when `trust_proxy` is set.
+pub fn client_ip(req: &HttpRequest, trust_proxy: bool) -> String {
+    let connection = req.connection_info();
+    let ip = if trust_proxy {
+        connection.realip_remote_addr()
+    } else {
+        connection.peer_addr()
+    };
+    ip.unwrap_or_default().to_string()
+}
+
+/// Writes the counted views to the database in one statement.
+pub async fn flush(state: &AppState) {
+    let counts = state.views.take_pending();
+    if counts.is_empty() {
+        return;
+    }
+
+    let (ids, views): (Vec<i32>, Vec<i64>) = counts.iter().map(|(id, count)| (*id, *count)).unzip();
+    if let Err(e) = Blogs::add_views(&state.pool, &ids, &views).await {
+        error::log_ignored(e);
+        state.views.restore(counts);
+    }
+}
+
+/// Background task flushing the view counter every `view_flush_interval`.
+pub async fn run_flusher(state: AppState) {
+    let mut interval = time::interval(state.config.view_flush_interval);
+    loop {
+        interval.tick().await;
+        flush(&state).await;
+    }
+}